@@ -0,0 +1,80 @@
+//! Overhead of injecting a hash (signalling, submitting the hash and [Hasher::finish]), compared to
+//! plain hashing of a key of the same size, for each protocol (as enabled by cargo features), each
+//! flow, and several [Hasher]s.
+//!
+//! Run all of them with `cargo bench`. Enable more protocols with cargo features: `mx` or `ndd`
+//! for `u8s`, `hpe` for `len` and `len_cookie`, and both for `str` (see `Cargo.toml`).
+#![cfg_attr(feature = "flags", feature(adt_const_params))]
+
+use core::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use hash_injector::{ProtocolFlags, SignalledInjectionBuildHasher, inject, new};
+use std::hash::DefaultHasher;
+
+const HASH: u64 = 0x0123_4567_89AB_CDEF;
+
+/// A key that injects its hash.
+struct Injecting<const PF: ProtocolFlags>(u64);
+impl<const PF: ProtocolFlags> Hash for Injecting<PF> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        inject::<_, PF>(state, self.0);
+    }
+}
+
+fn bench_hasher<const PF: ProtocolFlags, B: BuildHasher + Clone>(
+    c: &mut Criterion,
+    protocol: &str,
+    hasher: &str,
+    build: B,
+) {
+    let mut group = c.benchmark_group(format!("{protocol}/{hasher}"));
+    group.bench_function("plain", |b| b.iter(|| build.hash_one(black_box(HASH))));
+
+    let injecting = SignalledInjectionBuildHasher::<_, _, PF>::new(build.clone());
+    group.bench_function("inject", |b| {
+        b.iter(|| injecting.hash_one(Injecting::<PF>(black_box(HASH))))
+    });
+    group.finish();
+}
+
+fn bench_protocol<const PF: ProtocolFlags>(c: &mut Criterion, protocol: &str) {
+    bench_hasher::<PF, _>(
+        c,
+        protocol,
+        "DefaultHasher",
+        BuildHasherDefault::<DefaultHasher>::default(),
+    );
+    bench_hasher::<PF, _>(
+        c,
+        protocol,
+        "siphasher",
+        BuildHasherDefault::<siphasher::sip::SipHasher13>::default(),
+    );
+    bench_hasher::<PF, _>(c, protocol, "fxhash", fxhash::FxBuildHasher::default());
+}
+
+fn protocols(c: &mut Criterion) {
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    {
+        bench_protocol::<{ new::u8s::signal_first::u64() }>(c, "u8s/signal_first");
+        bench_protocol::<{ new::u8s::submit_first::u64() }>(c, "u8s/submit_first");
+    }
+    #[cfg(feature = "hpe")]
+    {
+        bench_protocol::<{ new::len::signal_first::u64() }>(c, "len/signal_first");
+        bench_protocol::<{ new::len::submit_first::u64() }>(c, "len/submit_first");
+        bench_protocol::<{ new::len_cookie::signal_first::u64() }>(c, "len_cookie/signal_first");
+        bench_protocol::<{ new::len_cookie::submit_first::u64() }>(c, "len_cookie/submit_first");
+    }
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    {
+        bench_protocol::<{ new::str::signal_first::u64() }>(c, "str/signal_first");
+        bench_protocol::<{ new::str::submit_first::u64() }>(c, "str/submit_first");
+    }
+    bench_protocol::<{ new::u8_stream::signal_first::u64() }>(c, "u8_stream/signal_first");
+    bench_protocol::<{ new::u8_stream::submit_first::u64() }>(c, "u8_stream/submit_first");
+    bench_protocol::<{ new::trusted::u64() }>(c, "trusted");
+}
+
+criterion_group!(benches, protocols);
+criterion_main!(benches);
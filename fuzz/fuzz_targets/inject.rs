@@ -0,0 +1,72 @@
+#![no_main]
+
+use core::hash::Hasher;
+use hash_injector::testing::RecordingHasher;
+use hash_injector::{ProtocolFlags, SignalledInjectionHasher, inject, new};
+use libfuzzer_sys::fuzz_target;
+
+/// Inject `hash` (directly, and also through a recording replay if `replayable`) and check that
+/// `finish()` returns it.
+fn check<const PF: ProtocolFlags>(hash: u64, replayable: bool) {
+    let mut hasher = SignalledInjectionHasher::<RecordingHasher, PF>::default();
+    inject::<_, PF>(&mut hasher, hash);
+    assert_eq!(hasher.finish(), hash);
+
+    if replayable {
+        let mut recording = RecordingHasher::default();
+        inject::<_, PF>(&mut recording, hash);
+
+        let mut hasher = SignalledInjectionHasher::<RecordingHasher, PF>::default();
+        recording.replay(&mut hasher);
+        assert_eq!(hasher.finish(), hash, "replayed {:?}", recording.calls);
+    }
+}
+
+/// One `check` per flow and hash type, for each signalling method `via` (a module under [new]).
+macro_rules! checks {
+    ($(($via:ident, $replayable:expr)),*) => {
+        &[
+            $(
+                (check::<{ new::$via::signal_first::u64() }>, $replayable),
+                (check::<{ new::$via::signal_first::i64() }>, $replayable),
+                (check::<{ new::$via::signal_first::u128() }>, $replayable),
+                (check::<{ new::$via::signal_first::i128() }>, $replayable),
+                (check::<{ new::$via::signal_first::bytes() }>, $replayable),
+                (check::<{ new::$via::submit_first::u64() }>, $replayable),
+                (check::<{ new::$via::submit_first::i64() }>, $replayable),
+                (check::<{ new::$via::submit_first::u128() }>, $replayable),
+                (check::<{ new::$via::submit_first::i128() }>, $replayable),
+                (check::<{ new::$via::submit_first::bytes() }>, $replayable),
+            )*
+            (check::<{ new::trusted::u64() }>, true),
+            (check::<{ new::trusted::i64() }>, true),
+            (check::<{ new::trusted::u128() }>, true),
+            (check::<{ new::trusted::i128() }>, true),
+            (check::<{ new::trusted::bytes() }>, true),
+            (check::<{ new::len_carrier::u64() }>, true),
+        ]
+    };
+}
+
+/// All valid protocols. Signals of `u8s` and `str` are recognized by address, hence they are not
+/// replayable.
+const CHECKS: &[(fn(u64, bool), bool)] = checks![
+    (u8s, false),
+    (str, false),
+    (len, true),
+    (len_cookie, true),
+    (u8_stream, true),
+    (usize, true)
+];
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, rest)) = data.split_first() else {
+        return;
+    };
+    let mut hash = [0u8; 8];
+    let len = rest.len().min(8);
+    hash[..len].copy_from_slice(&rest[..len]);
+
+    let (check, replayable) = CHECKS[selector as usize % CHECKS.len()];
+    check(u64::from_le_bytes(hash), replayable);
+});
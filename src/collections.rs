@@ -0,0 +1,297 @@
+use core::borrow::Borrow;
+use core::hash::{BuildHasherDefault, Hash, Hasher};
+use std::collections::{HashMap, HashSet};
+use std::hash::DefaultHasher;
+
+use crate::{ProtocolFlags, SignalledInjectionBuildHasher};
+
+/// The [core::hash::BuildHasher] of the collections here. Every key injects its hash, so the
+/// underlying [Hasher] (and its seed) doesn't matter.
+type Build<const PF: ProtocolFlags> =
+    SignalledInjectionBuildHasher<DefaultHasher, BuildHasherDefault<DefaultHasher>, PF>;
+
+/// A key stored together with its (externally computed) hash, which it injects.
+struct Injected<K, const PF: ProtocolFlags> {
+    hash: u64,
+    key: K,
+}
+
+/// A key and its hash, either owned ([Injected]), or borrowed (for lookups). Trait objects of this
+/// let [HashSet] look up an [Injected] without owning a key. `PF` tells their [Hash] implementation
+/// how to inject.
+trait KeyAndHash<K, const PF: ProtocolFlags> {
+    fn hash(&self) -> u64;
+    fn key(&self) -> &K;
+}
+impl<K, const PF: ProtocolFlags> KeyAndHash<K, PF> for Injected<K, PF> {
+    fn hash(&self) -> u64 {
+        self.hash
+    }
+    fn key(&self) -> &K {
+        &self.key
+    }
+}
+impl<K, const PF: ProtocolFlags> KeyAndHash<K, PF> for (&K, u64) {
+    fn hash(&self) -> u64 {
+        self.1
+    }
+    fn key(&self) -> &K {
+        self.0
+    }
+}
+impl<K, const PF: ProtocolFlags> Hash for dyn KeyAndHash<K, PF> + '_ {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        crate::inject::<_, PF>(state, KeyAndHash::hash(self));
+    }
+}
+impl<K: PartialEq, const PF: ProtocolFlags> PartialEq for dyn KeyAndHash<K, PF> + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        KeyAndHash::hash(self) == KeyAndHash::hash(other) && self.key() == other.key()
+    }
+}
+impl<K: Eq, const PF: ProtocolFlags> Eq for dyn KeyAndHash<K, PF> + '_ {}
+impl<'a, K: 'a, const PF: ProtocolFlags> Borrow<dyn KeyAndHash<K, PF> + 'a> for Injected<K, PF> {
+    fn borrow(&self) -> &(dyn KeyAndHash<K, PF> + 'a) {
+        self
+    }
+}
+// Consistent with the above, as required by [Borrow].
+impl<K, const PF: ProtocolFlags> Hash for Injected<K, PF> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        crate::inject::<_, PF>(state, self.hash);
+    }
+}
+impl<K: PartialEq, const PF: ProtocolFlags> PartialEq for Injected<K, PF> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.key == other.key
+    }
+}
+impl<K: Eq, const PF: ProtocolFlags> Eq for Injected<K, PF> {}
+
+/// A [HashSet] of keys whose hashes are computed elsewhere (for example, stored alongside the keys,
+/// or received over the network). Each operation takes the key together with its hash, and injects
+/// that hash (as per protocol `PF`) - so `K` doesn't need to implement [Hash] at all.
+///
+/// The caller is responsible for passing the same hash for equal keys. Different keys may have the
+/// same hash (they are told apart with [Eq]).
+///
+/// ```
+/// use hash_injector::{InjectingHashSet, new};
+///
+/// let mut set = InjectingHashSet::<_, { new::trusted::u64() }>::new();
+/// assert!(set.insert_hashed("one", 1));
+/// assert!(!set.insert_hashed("one", 1));
+/// assert!(set.contains_hash(&"one", 1));
+/// assert!(!set.contains_hash(&"two", 1));
+/// ```
+pub struct InjectingHashSet<K, const PF: ProtocolFlags> {
+    set: HashSet<Injected<K, PF>, Build<PF>>,
+}
+impl<K: Eq, const PF: ProtocolFlags> InjectingHashSet<K, PF> {
+    pub fn new() -> Self {
+        Self {
+            set: HashSet::with_hasher(Build::<PF>::new(BuildHasherDefault::default())),
+        }
+    }
+    /// Insert `key`, with its `hash`. Return whether it was inserted (that is, whether no equal key
+    /// was in the set).
+    pub fn insert_hashed(&mut self, key: K, hash: u64) -> bool {
+        self.set.insert(Injected { hash, key })
+    }
+    /// Whether the set contains `key`, looking it up by its `hash`.
+    pub fn contains_hash(&self, key: &K, hash: u64) -> bool {
+        self.set.contains(&(key, hash) as &dyn KeyAndHash<K, PF>)
+    }
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+}
+impl<K: Eq, const PF: ProtocolFlags> Default for InjectingHashSet<K, PF> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collect keys, each with its hash (as per [InjectingHashSet::insert_hashed]).
+///
+/// ```
+/// use hash_injector::{InjectingHashSet, new};
+///
+/// let pairs = vec![("one".to_owned(), 1), ("two".to_owned(), 2), ("one".to_owned(), 1)];
+/// let set: InjectingHashSet<String, { new::usize::signal_first::u64() }> =
+///     pairs.into_iter().collect();
+/// assert_eq!(set.len(), 2);
+/// assert!(set.contains_hash(&"two".to_owned(), 2));
+/// ```
+impl<K: Eq, const PF: ProtocolFlags> FromIterator<(K, u64)> for InjectingHashSet<K, PF> {
+    fn from_iter<I: IntoIterator<Item = (K, u64)>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for (key, hash) in iter {
+            set.insert_hashed(key, hash);
+        }
+        set
+    }
+}
+
+/// A [HashMap] whose keys' hashes are computed elsewhere, like [InjectingHashSet]. Each operation
+/// takes the key together with its hash, and injects that hash (as per protocol `PF`).
+///
+/// The caller is responsible for passing the same hash for equal keys.
+///
+/// ```
+/// use hash_injector::{InjectingHashMap, new};
+///
+/// let mut cache = InjectingHashMap::<_, _, { new::u8_stream::signal_first::u64() }>::new();
+/// assert_eq!(*cache.get_or_insert_with_hash("one", 1, || 10), 10);
+/// // A hit: The closure is not called.
+/// assert_eq!(*cache.get_or_insert_with_hash("one", 1, || unreachable!()), 10);
+/// assert_eq!(cache.get_hash(&"one", 1), Some(&10));
+/// ```
+pub struct InjectingHashMap<K, V, const PF: ProtocolFlags> {
+    map: HashMap<Injected<K, PF>, V, Build<PF>>,
+}
+impl<K: Eq, V, const PF: ProtocolFlags> InjectingHashMap<K, V, PF> {
+    pub fn new() -> Self {
+        Self {
+            map: HashMap::with_hasher(Build::<PF>::new(BuildHasherDefault::default())),
+        }
+    }
+    /// Insert `value` for `key`, with its `hash`. Return the previous value, if any.
+    pub fn insert_hashed(&mut self, key: K, hash: u64, value: V) -> Option<V> {
+        self.map.insert(Injected { hash, key }, value)
+    }
+    /// The value for `key`, looking it up by its `hash`.
+    pub fn get_hash(&self, key: &K, hash: u64) -> Option<&V> {
+        self.map.get(&(key, hash) as &dyn KeyAndHash<K, PF>)
+    }
+    /// The value for `key`, looking it up by its `hash`. If there is none, insert the result of
+    /// `f` first. This is the primitive of a cache.
+    pub fn get_or_insert_with_hash(&mut self, key: K, hash: u64, f: impl FnOnce() -> V) -> &mut V {
+        self.map.entry(Injected { hash, key }).or_insert_with(f)
+    }
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+impl<K: Eq, V, const PF: ProtocolFlags> Default for InjectingHashMap<K, V, PF> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::{String, ToString};
+
+    fn insert_and_contain<const PF: ProtocolFlags>() {
+        let mut set = InjectingHashSet::<String, PF>::new();
+        assert!(set.is_empty());
+        assert!(set.insert_hashed("a".to_string(), 10));
+        assert!(set.insert_hashed("b".to_string(), 20));
+        // A different key with the same hash.
+        assert!(set.insert_hashed("c".to_string(), 10));
+        assert!(!set.insert_hashed("a".to_string(), 10));
+        assert_eq!(set.len(), 3);
+
+        assert!(set.contains_hash(&"a".to_string(), 10));
+        assert!(set.contains_hash(&"c".to_string(), 10));
+        assert!(!set.contains_hash(&"d".to_string(), 10));
+        assert!(!set.contains_hash(&"a".to_string(), 20));
+    }
+
+    #[test]
+    fn insert_and_contain_trusted() {
+        insert_and_contain::<{ crate::new::trusted::u64() }>();
+    }
+
+    #[test]
+    fn insert_and_contain_signal_first() {
+        insert_and_contain::<{ crate::new::u8_stream::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn insert_and_contain_submit_first() {
+        insert_and_contain::<{ crate::new::usize::submit_first::i128() }>();
+    }
+
+    #[test]
+    fn many() {
+        let mut set = InjectingHashSet::<u32, { crate::new::trusted::u64() }>::default();
+        for i in 0..1000 {
+            assert!(set.insert_hashed(i, u64::from(i).wrapping_mul(0x9E37_79B9_7F4A_7C15)));
+        }
+        for i in 0..1000 {
+            assert!(set.contains_hash(&i, u64::from(i).wrapping_mul(0x9E37_79B9_7F4A_7C15)));
+        }
+        assert_eq!(set.len(), 1000);
+    }
+
+    #[test]
+    fn collect() {
+        let set: InjectingHashSet<u32, { crate::new::u8_stream::submit_first::u64() }> = (0..100)
+            .map(|i| (i % 10, u64::from(i % 10) << 32))
+            .collect();
+        assert_eq!(set.len(), 10);
+        assert!(set.contains_hash(&7, 7 << 32));
+        assert!(!set.contains_hash(&7, 7));
+    }
+
+    fn get_or_insert<const PF: ProtocolFlags>() {
+        let mut map = InjectingHashMap::<String, u32, PF>::new();
+        let calls = &core::cell::Cell::new(0);
+        let compute = |value| {
+            move || {
+                calls.set(calls.get() + 1);
+                value
+            }
+        };
+        // Misses.
+        assert_eq!(
+            *map.get_or_insert_with_hash("a".to_string(), 10, compute(1)),
+            1
+        );
+        // A different key with the same hash.
+        assert_eq!(
+            *map.get_or_insert_with_hash("c".to_string(), 10, compute(3)),
+            3
+        );
+        assert_eq!(calls.get(), 2);
+        assert_eq!(map.len(), 2);
+        // Hits.
+        assert_eq!(
+            *map.get_or_insert_with_hash("a".to_string(), 10, compute(2)),
+            1
+        );
+        *map.get_or_insert_with_hash("c".to_string(), 10, compute(4)) += 10;
+        assert_eq!(calls.get(), 2);
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.get_hash(&"a".to_string(), 10), Some(&1));
+        assert_eq!(map.get_hash(&"c".to_string(), 10), Some(&13));
+        assert_eq!(map.get_hash(&"a".to_string(), 20), None);
+        assert_eq!(map.insert_hashed("a".to_string(), 10, 5), Some(1));
+        assert_eq!(map.get_hash(&"a".to_string(), 10), Some(&5));
+    }
+
+    #[test]
+    fn get_or_insert_trusted() {
+        get_or_insert::<{ crate::new::trusted::u64() }>();
+    }
+
+    #[test]
+    fn get_or_insert_signal_first() {
+        get_or_insert::<{ crate::new::u8_stream::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn get_or_insert_submit_first() {
+        get_or_insert::<{ crate::new::usize::submit_first::i128() }>();
+    }
+}
@@ -0,0 +1,258 @@
+use core::hash::Hasher;
+
+use crate::flags::SignalVia;
+use crate::signal::is_check_flow_sentinel;
+use crate::{ProtocolFlags, SignalledInjectionHasher};
+
+/// Combine `hash` into `digest`, as [CombiningInjector] does for each injected hash. Use it where
+/// you need the expected digest directly (for example, to compare with one computed elsewhere).
+#[must_use]
+pub const fn combine_hash(digest: u64, hash: u64) -> u64 {
+    digest.wrapping_mul(0x0000_0100_0000_01B3) ^ hash
+}
+
+/// A [Hasher] that receives any number of injected hashes, one after another (for example, from
+/// items of a collection, each of which calls [crate::inject]), and combines them into one digest
+/// with [combine_hash], starting from zero. [Hasher::finish] returns the digest. So the digest
+/// depends on the order of the items.
+///
+/// Unlike [SignalledInjectionHasher] (where the last injected hash wins - if injecting more than
+/// once were allowed), this produces one value from all of them.
+///
+/// Write nothing but injections (as per protocol `PF`): ordinary data is not combined. `H` only
+/// receives ordinary data (and hashes submitted by submit-first protocols), so it doesn't matter.
+///
+/// With `chk-hash`, [crate::inject] asserts that [Hasher::finish] returns the injected hash - but
+/// [CombiningInjector] (from the second injection on) does not.
+///
+/// ```
+/// use core::hash::{Hash, Hasher};
+/// use hash_injector::{CombiningInjector, ProtocolFlags, combine_hash, inject, new};
+/// use std::hash::DefaultHasher;
+///
+/// const PF: ProtocolFlags = new::u8_stream::signal_first::u64();
+/// let mut hasher = CombiningInjector::<DefaultHasher, PF>::default();
+/// inject::<_, PF>(&mut hasher, 42);
+/// # #[cfg(not(feature = "chk-hash"))]
+/// inject::<_, PF>(&mut hasher, 7);
+/// # #[cfg(not(feature = "chk-hash"))]
+/// assert_eq!(hasher.finish(), combine_hash(combine_hash(0, 42), 7));
+/// ```
+pub struct CombiningInjector<H: Hasher, const PF: ProtocolFlags> {
+    hasher: SignalledInjectionHasher<H, PF>,
+    digest: u64,
+    /// Whether the hash received by `hasher` was combined into `digest` already. Then the state
+    /// of `hasher` is reset on the next write (unless that is a `chk-flow` sentinel, which belongs
+    /// to the same injection).
+    combined: bool,
+}
+
+impl<H: Hasher, const PF: ProtocolFlags> CombiningInjector<H, PF> {
+    pub fn new(hasher: H) -> Self {
+        Self {
+            hasher: hasher.into(),
+            digest: 0,
+            combined: false,
+        }
+    }
+    #[inline(always)]
+    fn before_write(&mut self, check_flow_sentinel: bool) {
+        if self.combined && !check_flow_sentinel {
+            self.hasher.reset_state();
+            self.combined = false;
+        }
+    }
+    #[inline(always)]
+    fn after_write(&mut self) {
+        if !self.combined && self.hasher.is_hash_received() {
+            self.digest = combine_hash(self.digest, self.hasher.finish());
+            self.combined = true;
+        }
+    }
+}
+impl<H: Hasher + Default, const PF: ProtocolFlags> Default for CombiningInjector<H, PF> {
+    fn default() -> Self {
+        Self::new(H::default())
+    }
+}
+
+impl<H: Hasher, const PF: ProtocolFlags> Hasher for CombiningInjector<H, PF> {
+    fn finish(&self) -> u64 {
+        self.digest
+    }
+    fn write(&mut self, bytes: &[u8]) {
+        self.before_write(is_check_flow_sentinel(
+            PF,
+            SignalVia::U8s,
+            bytes.as_ptr(),
+            bytes.len(),
+        ));
+        self.hasher.write(bytes);
+        self.after_write();
+    }
+    fn write_u8(&mut self, i: u8) {
+        self.before_write(false);
+        self.hasher.write_u8(i);
+        self.after_write();
+    }
+    fn write_u16(&mut self, i: u16) {
+        self.before_write(false);
+        self.hasher.write_u16(i);
+        self.after_write();
+    }
+    fn write_u32(&mut self, i: u32) {
+        self.before_write(false);
+        self.hasher.write_u32(i);
+        self.after_write();
+    }
+    fn write_u64(&mut self, i: u64) {
+        self.before_write(false);
+        self.hasher.write_u64(i);
+        self.after_write();
+    }
+    fn write_u128(&mut self, i: u128) {
+        self.before_write(false);
+        self.hasher.write_u128(i);
+        self.after_write();
+    }
+    fn write_usize(&mut self, i: usize) {
+        self.before_write(false);
+        self.hasher.write_usize(i);
+        self.after_write();
+    }
+    fn write_i8(&mut self, i: i8) {
+        self.before_write(false);
+        self.hasher.write_i8(i);
+        self.after_write();
+    }
+    fn write_i16(&mut self, i: i16) {
+        self.before_write(false);
+        self.hasher.write_i16(i);
+        self.after_write();
+    }
+    fn write_i32(&mut self, i: i32) {
+        self.before_write(false);
+        self.hasher.write_i32(i);
+        self.after_write();
+    }
+    fn write_i64(&mut self, i: i64) {
+        self.before_write(false);
+        self.hasher.write_i64(i);
+        self.after_write();
+    }
+    fn write_i128(&mut self, i: i128) {
+        self.before_write(false);
+        self.hasher.write_i128(i);
+        self.after_write();
+    }
+    fn write_isize(&mut self, i: isize) {
+        self.before_write(false);
+        self.hasher.write_isize(i);
+        self.after_write();
+    }
+    #[cfg(feature = "hpe")]
+    fn write_length_prefix(&mut self, len: usize) {
+        self.before_write(is_check_flow_sentinel(
+            PF,
+            SignalVia::Len,
+            core::ptr::null(),
+            len,
+        ));
+        self.hasher.write_length_prefix(len);
+        self.after_write();
+    }
+    #[cfg(feature = "hpe")]
+    fn write_str(&mut self, s: &str) {
+        self.before_write(is_check_flow_sentinel(
+            PF,
+            SignalVia::Str,
+            s.as_ptr(),
+            s.len(),
+        ));
+        self.hasher.write_str(s);
+        self.after_write();
+    }
+}
+
+/// With `chk-hash`, [crate::inject] asserts that the [Hasher] returns the injected hash - but
+/// [CombiningInjector] does not.
+#[cfg(all(test, not(feature = "chk-hash")))]
+mod tests {
+    use super::*;
+    use core::hash::Hash;
+    use std::hash::DefaultHasher;
+
+    const HASHES: [u64; 4] = [1, 2, 3, 0x0123_4567_89AB_CDEF];
+
+    fn combines<const PF: ProtocolFlags>() {
+        let mut hasher = CombiningInjector::<DefaultHasher, PF>::default();
+        assert_eq!(hasher.finish(), 0);
+        for hash in &HASHES[..3] {
+            crate::inject::<_, PF>(&mut hasher, *hash);
+        }
+        assert_eq!(hasher.finish(), 0x0003_6400_0002_DFC0);
+        crate::inject::<_, PF>(&mut hasher, HASHES[3]);
+        assert_eq!(
+            hasher.finish(),
+            HASHES
+                .iter()
+                .fold(0, |digest, &hash| combine_hash(digest, hash))
+        );
+    }
+
+    #[test]
+    fn combines_trusted() {
+        combines::<{ crate::new::trusted::u64() }>();
+    }
+
+    #[test]
+    fn combines_u8_stream() {
+        combines::<{ crate::new::u8_stream::signal_first::u64() }>();
+        combines::<{ crate::new::u8_stream::submit_first::i128() }>();
+    }
+
+    #[test]
+    fn combines_usize() {
+        combines::<{ crate::new::usize::signal_first::bytes() }>();
+        combines::<{ crate::new::usize::submit_first::u64() }>();
+    }
+
+    #[cfg(feature = "hpe")]
+    #[test]
+    fn combines_len() {
+        combines::<{ crate::new::len::signal_first::u64() }>();
+        combines::<{ crate::new::len::submit_first::i64() }>();
+        combines::<{ crate::new::len_cookie::signal_first::u128() }>();
+        combines::<{ crate::new::len_cookie::submit_first::u64() }>();
+    }
+
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    #[test]
+    fn combines_u8s() {
+        combines::<{ crate::new::u8s::signal_first::u64() }>();
+        combines::<{ crate::new::u8s::submit_first::u64() }>();
+    }
+
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    #[test]
+    fn combines_str() {
+        combines::<{ crate::new::str::signal_first::u64() }>();
+        combines::<{ crate::new::str::submit_first::u64() }>();
+    }
+
+    /// The items of a slice inject one after another.
+    #[test]
+    fn slice_of_injecting_keys() {
+        const PF: ProtocolFlags = crate::new::usize::signal_first::u64();
+        struct Key(u64);
+        impl Hash for Key {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                crate::inject::<_, PF>(state, self.0);
+            }
+        }
+        let keys = [Key(10), Key(20)];
+        let mut hasher = CombiningInjector::<DefaultHasher, PF>::default();
+        Hash::hash_slice(&keys, &mut hasher);
+        assert_eq!(hasher.finish(), combine_hash(combine_hash(0, 10), 20));
+    }
+}
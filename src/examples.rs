@@ -0,0 +1,54 @@
+//! Executable examples.
+//!
+//! # Injecting a hash computed by a different hasher family
+//!
+//! The injected `u64` does not need to come from the [core::hash::Hasher] wrapped by
+//! [crate::SignalledInjectionHasher]. It can be computed out-of-band, by any hash function (for
+//! example, xxhash). It fully replaces whatever the wrapped [core::hash::Hasher] (here
+//! [std::hash::DefaultHasher]) would produce. So the bucket assignment (in a
+//! [std::collections::HashMap]) is driven by that out-of-band hash.
+//!
+//! Below the out-of-band hash function is FNV-1a, to keep this crate free of dependencies. It uses
+//! a trusted protocol ([crate::new::trusted]), because all keys of the map inject their hashes.
+//!
+//! ```
+//! use core::hash::{BuildHasher, Hash, Hasher};
+//! use hash_injector::{ProtocolFlags, SignalledInjectionBuildHasher, inject, new};
+//! use std::collections::HashMap;
+//!
+//! /// Out-of-band hash function (FNV-1a).
+//! fn fnv1a(bytes: &[u8]) -> u64 {
+//!     bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+//!         (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+//!     })
+//! }
+//!
+//! const PF: ProtocolFlags = new::trusted::u64();
+//!
+//! #[derive(PartialEq, Eq)]
+//! struct Key {
+//!     name: &'static str,
+//!     hash: u64,
+//! }
+//! impl Key {
+//!     fn new(name: &'static str) -> Self {
+//!         Self { name, hash: fnv1a(name.as_bytes()) }
+//!     }
+//! }
+//! impl Hash for Key {
+//!     fn hash<H: Hasher>(&self, state: &mut H) {
+//!         inject::<_, PF>(state, self.hash);
+//!     }
+//! }
+//!
+//! let build = SignalledInjectionBuildHasher::<_, _, PF>::random();
+//! let mut map = HashMap::with_hasher(build);
+//! for (value, name) in ["zero", "one", "two"].into_iter().enumerate() {
+//!     map.insert(Key::new(name), value);
+//! }
+//!
+//! assert_eq!(map.hasher().hash_one(Key::new("one")), fnv1a(b"one"));
+//! assert_eq!(map.get(&Key::new("one")), Some(&1));
+//! assert_eq!(map.get(&Key::new("two")), Some(&2));
+//! assert_eq!(map.get(&Key::new("three")), None);
+//! ```
@@ -0,0 +1,73 @@
+/// Names of the cargo features of this crate that are enabled (in the order of `Cargo.toml`). Handy
+/// for bug reports, or to confirm that a build has the signalling and checks that you expect.
+///
+/// ```
+/// let features = hash_injector::active_features();
+/// assert_eq!(features.contains(&"hpe"), cfg!(feature = "hpe"));
+/// ```
+#[must_use]
+pub const fn active_features() -> &'static [&'static str] {
+    &[
+        #[cfg(feature = "std")]
+        "std",
+        #[cfg(feature = "mx")]
+        "mx",
+        #[cfg(feature = "ndd")]
+        "ndd",
+        #[cfg(feature = "raw-entry")]
+        "raw-entry",
+        #[cfg(feature = "fxhash")]
+        "fxhash",
+        #[cfg(feature = "seahash")]
+        "seahash",
+        #[cfg(feature = "testing")]
+        "testing",
+        #[cfg(feature = "hpe")]
+        "hpe",
+        #[cfg(feature = "flags")]
+        "flags",
+        #[cfg(feature = "no-drop-guard")]
+        "no-drop-guard",
+        #[cfg(feature = "chk-hash")]
+        "chk-hash",
+        #[cfg(feature = "chk-flow")]
+        "chk-flow",
+        #[cfg(feature = "chk-mixing")]
+        "chk-mixing",
+        #[cfg(feature = "chk-empty")]
+        "chk-empty",
+        #[cfg(feature = "chk-width")]
+        "chk-width",
+        #[cfg(feature = "chk-signal-method")]
+        "chk-signal-method",
+        #[cfg(feature = "chk")]
+        "chk",
+        #[cfg(feature = "chk-details")]
+        "chk-details",
+        #[cfg(feature = "check-all")]
+        "check-all",
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_cfg() {
+        let features = active_features();
+        for (enabled, name) in [
+            (cfg!(feature = "std"), "std"),
+            (cfg!(feature = "mx"), "mx"),
+            (cfg!(feature = "hpe"), "hpe"),
+            (cfg!(feature = "flags"), "flags"),
+            (cfg!(feature = "chk-flow"), "chk-flow"),
+            (cfg!(feature = "chk"), "chk"),
+        ] {
+            assert_eq!(features.contains(&name), enabled, "{name}");
+        }
+        for (i, name) in features.iter().enumerate() {
+            assert!(!features[..i].contains(name));
+        }
+    }
+}
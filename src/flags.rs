@@ -1,3 +1,4 @@
+use core::fmt;
 #[cfg(feature = "flags")]
 use core::marker::ConstParamTy;
 
@@ -12,55 +13,80 @@ pub type ProtocolFlags = ProtocolFlagsImpl;
 #[cfg(not(feature = "flags"))]
 type ProtocolFlagsImpl = u8;
 
+/// Which `Hasher::write_xxx` a protocol submits the hash with. See [try_hash_via].
 #[cfg_attr(feature = "flags", derive(ConstParamTy))]
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub(crate) enum HashVia {
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum HashVia {
     U64,
     I64,
     U128,
     I128,
+    /// A `u64` as its 8 little-endian bytes, via [`core::hash::Hasher::write`] (for
+    /// [core::hash::Hasher]s that specialize only `write`).
+    Bytes,
 }
 
+/// How a protocol signals - one variant per module of [new]. See [try_signal_via].
 #[cfg_attr(feature = "flags", derive(ConstParamTy))]
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub(crate) enum SignalVia {
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SignalVia {
     U8s,
     Len,
     Str,
+    Trusted,
+    U8Stream,
+    LenCookie,
+    Usize,
+    LenCarrier,
 }
 
 #[cfg(feature = "flags")]
 /// Type for const generic parameter `PF`.
-#[derive(ConstParamTy, Clone, Copy, PartialEq, Eq)]
+#[derive(ConstParamTy, Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct ProtocolFlagsImpl {
     signal_via: SignalVia,
     signal_first: bool,
     hash_via: HashVia,
 }
 
+// The bits of the (non-"flags") representation are the canonical encoding. See [flags_to_bits].
+#[cfg(not(feature = "flags"))]
+const FLAGS_MASK_VIA_U8S: ProtocolFlags = 0b000;
+#[cfg(not(feature = "flags"))]
+const FLAGS_MASK_VIA_LEN: ProtocolFlags = 0b001;
 #[cfg(not(feature = "flags"))]
-const FLAGS_MASK_VIA_U8S: ProtocolFlags = 0b00;
+const FLAGS_MASK_VIA_STR: ProtocolFlags = 0b010;
 #[cfg(not(feature = "flags"))]
-const FLAGS_MASK_VIA_LEN: ProtocolFlags = 0b01;
+const FLAGS_MASK_VIA_TRUSTED: ProtocolFlags = 0b011;
 #[cfg(not(feature = "flags"))]
-const FLAGS_MASK_VIA_STR: ProtocolFlags = 0b10;
+const FLAGS_MASK_VIA_U8_STREAM: ProtocolFlags = 0b100;
 #[cfg(not(feature = "flags"))]
-const FLAGS_BITS_VIA: ProtocolFlags = 0b11;
+const FLAGS_MASK_VIA_LEN_COOKIE: ProtocolFlags = 0b101;
+#[cfg(not(feature = "flags"))]
+const FLAGS_MASK_VIA_USIZE: ProtocolFlags = 0b110;
+#[cfg(not(feature = "flags"))]
+const FLAGS_MASK_VIA_LEN_CARRIER: ProtocolFlags = 0b111;
+#[cfg(not(feature = "flags"))]
+const FLAGS_BITS_VIA: ProtocolFlags = 0b111;
 
 #[cfg(not(feature = "flags"))]
-const FLAGS_BIT_SIGNAL_FIRST: ProtocolFlags = 0b100;
+const FLAGS_BIT_SIGNAL_FIRST: ProtocolFlags = 0b1000;
 
 #[cfg(not(feature = "flags"))]
-const FLAGS_MASK_HASH_U64: ProtocolFlags = 0b0000;
+const FLAGS_MASK_HASH_U64: ProtocolFlags = 0b000_0000;
+#[cfg(not(feature = "flags"))]
+const FLAGS_MASK_HASH_I64: ProtocolFlags = 0b001_0000;
 #[cfg(not(feature = "flags"))]
-const FLAGS_MASK_HASH_I64: ProtocolFlags = 0b1000;
+const FLAGS_MASK_HASH_U128: ProtocolFlags = 0b010_0000;
 #[cfg(not(feature = "flags"))]
-const FLAGS_MASK_HASH_U128: ProtocolFlags = 0b10000;
+const FLAGS_MASK_HASH_I128: ProtocolFlags = 0b011_0000;
 #[cfg(not(feature = "flags"))]
-const FLAGS_MASK_HASH_I128: ProtocolFlags = 0b11000;
+const FLAGS_MASK_HASH_BYTES: ProtocolFlags = 0b100_0000;
+#[cfg(not(feature = "flags"))]
+const FLAGS_BITS_HASH: ProtocolFlags = 0b111_0000;
 
 #[cfg(not(feature = "flags"))]
-const FLAGS_MAX: ProtocolFlags = 0b11110;
+const FLAGS_MAX: ProtocolFlags = 0b100_1110;
 
 /// Whether this protocol signals with a special static u8 slice `&[u8]`, that is, via
 ///  [`core::hash::Hasher::write`].
@@ -103,6 +129,79 @@ pub const fn is_signal_via_str(flags: ProtocolFlags) -> bool {
     }
 }
 
+/// Whether this protocol does NOT signal at all, because it trusts that the [core::hash::Hasher] is
+/// [crate::SignalledInjectionHasher]. Then the first (and only) write of the hash is the injected
+/// hash.
+pub const fn is_signal_via_trusted(flags: ProtocolFlags) -> bool {
+    #[cfg(not(feature = "flags"))]
+    {
+        debug_assert!(flags <= FLAGS_MAX);
+        flags & FLAGS_BITS_VIA == FLAGS_MASK_VIA_TRUSTED
+    }
+    #[cfg(feature = "flags")]
+    {
+        matches!(flags.signal_via, SignalVia::Trusted)
+    }
+}
+
+/// Whether this protocol signals with a sequence of [`core::hash::Hasher::write_u8`] calls (for
+/// [core::hash::Hasher]s that specialize only `write_u8`).
+pub const fn is_signal_via_u8_stream(flags: ProtocolFlags) -> bool {
+    #[cfg(not(feature = "flags"))]
+    {
+        debug_assert!(flags <= FLAGS_MAX);
+        flags & FLAGS_BITS_VIA == FLAGS_MASK_VIA_U8_STREAM
+    }
+    #[cfg(feature = "flags")]
+    {
+        matches!(flags.signal_via, SignalVia::U8Stream)
+    }
+}
+
+/// Whether this protocol signals with a zero length (via
+/// [`core::hash::Hasher::write_length_prefix`]) immediately followed by a cookie (via
+/// [`core::hash::Hasher::write_u64`]).
+pub const fn is_signal_via_len_cookie(flags: ProtocolFlags) -> bool {
+    #[cfg(not(feature = "flags"))]
+    {
+        debug_assert!(flags <= FLAGS_MAX);
+        flags & FLAGS_BITS_VIA == FLAGS_MASK_VIA_LEN_COOKIE
+    }
+    #[cfg(feature = "flags")]
+    {
+        matches!(flags.signal_via, SignalVia::LenCookie)
+    }
+}
+
+/// Whether this protocol signals with `usize::MAX` (via [`core::hash::Hasher::write_usize`])
+/// immediately followed by a cookie (via [`core::hash::Hasher::write_u64`]).
+pub const fn is_signal_via_usize(flags: ProtocolFlags) -> bool {
+    #[cfg(not(feature = "flags"))]
+    {
+        debug_assert!(flags <= FLAGS_MAX);
+        flags & FLAGS_BITS_VIA == FLAGS_MASK_VIA_USIZE
+    }
+    #[cfg(feature = "flags")]
+    {
+        matches!(flags.signal_via, SignalVia::Usize)
+    }
+}
+
+/// Whether this protocol signals with a fictitious length (via
+/// [`core::hash::Hasher::write_length_prefix`]), and then carries the hash in two more lengths
+/// (its high and low 32 bits).
+pub const fn is_signal_via_len_carrier(flags: ProtocolFlags) -> bool {
+    #[cfg(not(feature = "flags"))]
+    {
+        debug_assert!(flags <= FLAGS_MAX);
+        flags & FLAGS_BITS_VIA == FLAGS_MASK_VIA_LEN_CARRIER
+    }
+    #[cfg(feature = "flags")]
+    {
+        matches!(flags.signal_via, SignalVia::LenCarrier)
+    }
+}
+
 /// Whether the protocol signals before it submits the hash.
 pub const fn is_signal_first(flags: ProtocolFlags) -> bool {
     #[cfg(not(feature = "flags"))]
@@ -134,7 +233,7 @@ pub const fn is_hash_via_u64(flags: ProtocolFlags) -> bool {
     {
         #[cfg(feature = "chk")]
         assert!(flags <= FLAGS_MAX);
-        flags & FLAGS_MASK_HASH_U64 == FLAGS_MASK_HASH_U64
+        flags & FLAGS_BITS_HASH == FLAGS_MASK_HASH_U64
     }
     #[cfg(feature = "flags")]
     {
@@ -146,7 +245,7 @@ pub const fn is_hash_via_i64(flags: ProtocolFlags) -> bool {
     {
         #[cfg(feature = "chk")]
         assert!(flags <= FLAGS_MAX);
-        flags & FLAGS_MASK_HASH_I64 == FLAGS_MASK_HASH_I64
+        flags & FLAGS_BITS_HASH == FLAGS_MASK_HASH_I64
     }
     #[cfg(feature = "flags")]
     {
@@ -158,7 +257,7 @@ pub const fn is_hash_via_u128(flags: ProtocolFlags) -> bool {
     {
         #[cfg(feature = "chk")]
         assert!(flags <= FLAGS_MAX);
-        flags & FLAGS_MASK_HASH_U128 == FLAGS_MASK_HASH_U128
+        flags & FLAGS_BITS_HASH == FLAGS_MASK_HASH_U128
     }
     #[cfg(feature = "flags")]
     {
@@ -170,7 +269,7 @@ pub const fn is_hash_via_i128(flags: ProtocolFlags) -> bool {
     {
         #[cfg(feature = "chk")]
         assert!(flags <= FLAGS_MAX);
-        flags & FLAGS_MASK_HASH_I128 == FLAGS_MASK_HASH_I128
+        flags & FLAGS_BITS_HASH == FLAGS_MASK_HASH_I128
     }
     #[cfg(feature = "flags")]
     {
@@ -178,6 +277,43 @@ pub const fn is_hash_via_i128(flags: ProtocolFlags) -> bool {
     }
 }
 
+/// Whether the protocol submits the hash as 8 little-endian bytes, via
+/// [`core::hash::Hasher::write`].
+pub const fn is_hash_via_bytes(flags: ProtocolFlags) -> bool {
+    #[cfg(not(feature = "flags"))]
+    {
+        #[cfg(feature = "chk")]
+        assert!(flags <= FLAGS_MAX);
+        flags & FLAGS_BITS_HASH == FLAGS_MASK_HASH_BYTES
+    }
+    #[cfg(feature = "flags")]
+    {
+        matches!(flags.hash_via, HashVia::Bytes)
+    }
+}
+
+/// How `flags` signals, or [None] if `flags` is not valid (see [is_valid]). Unlike other
+/// introspection of [ProtocolFlags], this doesn't panic on an invalid bit pattern, so it can
+/// introspect flags that were constructed at runtime.
+#[must_use]
+pub const fn try_signal_via(flags: ProtocolFlags) -> Option<SignalVia> {
+    if is_valid(flags) {
+        Some(signal_via(flags))
+    } else {
+        None
+    }
+}
+
+/// Like [try_signal_via], but which `Hasher::write_xxx` `flags` submits the hash with.
+#[must_use]
+pub const fn try_hash_via(flags: ProtocolFlags) -> Option<HashVia> {
+    if is_valid(flags) {
+        Some(hash_via(flags))
+    } else {
+        None
+    }
+}
+
 pub(crate) const fn signal_via(flags: ProtocolFlags) -> SignalVia {
     if is_signal_via_u8s(flags) {
         SignalVia::U8s
@@ -185,6 +321,16 @@ pub(crate) const fn signal_via(flags: ProtocolFlags) -> SignalVia {
         SignalVia::Len
     } else if is_signal_via_str(flags) {
         SignalVia::Str
+    } else if is_signal_via_trusted(flags) {
+        SignalVia::Trusted
+    } else if is_signal_via_u8_stream(flags) {
+        SignalVia::U8Stream
+    } else if is_signal_via_len_cookie(flags) {
+        SignalVia::LenCookie
+    } else if is_signal_via_usize(flags) {
+        SignalVia::Usize
+    } else if is_signal_via_len_carrier(flags) {
+        SignalVia::LenCarrier
     } else {
         unreachable!()
     }
@@ -199,11 +345,46 @@ pub(crate) const fn hash_via(flags: ProtocolFlags) -> HashVia {
         HashVia::U128
     } else if is_hash_via_i128(flags) {
         HashVia::I128
+    } else if is_hash_via_bytes(flags) {
+        HashVia::Bytes
     } else {
         unreachable!()
     }
 }
 
+/// Whether the cargo features that signalling as per `flags` requires are enabled: `mx` or `ndd`
+/// for `u8s` and `str`, and `hpe` for `len`, `str`, `len_cookie` and `len_carrier`.
+pub(crate) const fn is_enabled(flags: ProtocolFlags) -> bool {
+    match signal_via(flags) {
+        SignalVia::U8s => cfg!(any(feature = "mx", feature = "ndd")),
+        SignalVia::Len | SignalVia::LenCookie | SignalVia::LenCarrier => cfg!(feature = "hpe"),
+        SignalVia::Str => cfg!(all(any(feature = "mx", feature = "ndd"), feature = "hpe")),
+        SignalVia::Trusted | SignalVia::U8Stream | SignalVia::Usize => true,
+    }
+}
+
+/// Assert that `flags` is valid, and that its protocol is enabled. With `chk`, call this in a
+/// `const` block, so that a protocol that could only reach an `unreachable!()` branch at runtime
+/// fails to compile instead.
+#[track_caller]
+pub(crate) const fn assert_usable(flags: ProtocolFlags) {
+    assert!(is_valid(flags), "Invalid ProtocolFlags.");
+    assert!(
+        is_enabled(flags),
+        "ProtocolFlags of a protocol whose cargo features are not enabled."
+    );
+}
+
+/// How many bytes the protocol's hash submission writes (through the respective
+/// `Hasher::write_xxx`). Useful when sizing buffers of custom [core::hash::Hasher]s.
+#[must_use]
+pub const fn hash_via_bytes(flags: ProtocolFlags) -> usize {
+    match hash_via(flags) {
+        HashVia::U64 | HashVia::I64 | HashVia::Bytes => 8,
+        HashVia::U128 | HashVia::I128 => 16,
+    }
+}
+
 /// A helper enum that allows us to use `match ... {...}`` statements, rather than
 ///
 /// `if is_submit_first(PF) {...} else {...}`.
@@ -222,6 +403,150 @@ pub const fn flow(flags: ProtocolFlags) -> Flow {
     }
 }
 
+/// Canonical, stable encoding of [ProtocolFlags] as [u8], for example for a protocol tag in a
+/// persisted file format. It does not change across versions (new variants only take values that
+/// are unused so far):
+/// - bits 0..=2: how the protocol signals: `0` = u8 slice, `1` = length prefix, `2` = string slice,
+///   `3` = trusted (no signal), `4` = stream of `u8`s, `5` = zero length followed by a cookie,
+///   `6` = `usize::MAX` followed by a cookie, `7` = length prefix carrying the hash (`len_carrier`,
+///   valid only with bit 3 set and bits 4..=6 zero: it signals first, with a `u64`). These bits are
+///   all taken, so any new way of signalling needs bit 7,
+/// - bit 3: `1` if the protocol signals first, `0` if it submits first,
+/// - bits 4..=6: how the hash is submitted: `0` = `u64`, `1` = `i64`, `2` = `u128`, `3` = `i128`,
+///   `4` = `u64` as little-endian bytes,
+/// - bit 7: reserved (`0`).
+///
+/// The encoding is the same regardless of cargo features.
+#[must_use]
+pub const fn flags_to_bits(flags: ProtocolFlags) -> u8 {
+    #[cfg(not(feature = "flags"))]
+    {
+        debug_assert!(flags <= FLAGS_MAX);
+        flags
+    }
+    #[cfg(feature = "flags")]
+    {
+        let via = match flags.signal_via {
+            SignalVia::U8s => 0,
+            SignalVia::Len => 1,
+            SignalVia::Str => 2,
+            SignalVia::Trusted => 3,
+            SignalVia::U8Stream => 4,
+            SignalVia::LenCookie => 5,
+            SignalVia::Usize => 6,
+            SignalVia::LenCarrier => 7,
+        };
+        let signal_first = if flags.signal_first { 0b1000 } else { 0 };
+        let hash = match flags.hash_via {
+            HashVia::U64 => 0,
+            HashVia::I64 => 1,
+            HashVia::U128 => 2,
+            HashVia::I128 => 3,
+            HashVia::Bytes => 4,
+        };
+        via | signal_first | hash << 4
+    }
+}
+
+/// Inverse of [flags_to_bits]. Return [None] if `bits` is not a valid encoding.
+///
+/// This does NOT check whether the protocol is enabled by cargo features (for example, `u8s`
+/// protocols need `mx` or `ndd` feature).
+#[must_use = "`None` means that the bits are not a valid encoding"]
+pub const fn flags_from_bits(bits: u8) -> Option<ProtocolFlags> {
+    let via = bits & 0b111;
+    let signal_first = bits & 0b1000 != 0;
+    let hash = bits >> 4;
+    // Trusted protocols don't signal, so they don't signal first, either. The `len_carrier`
+    // protocol signals first, and it carries a u64.
+    if hash > 4 || (via == 3 && signal_first) || (via == 7 && !(signal_first && hash == 0)) {
+        return None;
+    }
+    #[cfg(not(feature = "flags"))]
+    {
+        Some(bits)
+    }
+    #[cfg(feature = "flags")]
+    {
+        Some(ProtocolFlags {
+            signal_via: match via {
+                0 => SignalVia::U8s,
+                1 => SignalVia::Len,
+                2 => SignalVia::Str,
+                3 => SignalVia::Trusted,
+                4 => SignalVia::U8Stream,
+                5 => SignalVia::LenCookie,
+                6 => SignalVia::Usize,
+                _ => SignalVia::LenCarrier,
+            },
+            signal_first,
+            hash_via: match hash {
+                0 => HashVia::U64,
+                1 => HashVia::I64,
+                2 => HashVia::U128,
+                3 => HashVia::I128,
+                _ => HashVia::Bytes,
+            },
+        })
+    }
+}
+
+/// Error of `ProtocolFlags::try_from(u8)`: the byte is not a valid encoding (see [flags_to_bits]).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InvalidFlagsBits(pub u8);
+impl fmt::Display for InvalidFlagsBits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:#010b} is not a valid encoding of ProtocolFlags.",
+            self.0
+        )
+    }
+}
+impl core::error::Error for InvalidFlagsBits {}
+
+// Without `flags` cargo feature, ProtocolFlags is `u8`, so `ProtocolFlags::try_from(u8)` is core's
+// blanket (infallible) impl, and it does NOT validate. Use [flags_from_bits] instead.
+#[cfg(feature = "flags")]
+/// Same as [flags_from_bits].
+impl TryFrom<u8> for ProtocolFlagsImpl {
+    type Error = InvalidFlagsBits;
+    fn try_from(bits: u8) -> Result<Self, Self::Error> {
+        flags_from_bits(bits).ok_or(InvalidFlagsBits(bits))
+    }
+}
+#[cfg(feature = "flags")]
+/// Same as [flags_to_bits].
+impl From<ProtocolFlagsImpl> for u8 {
+    fn from(flags: ProtocolFlagsImpl) -> Self {
+        flags_to_bits(flags)
+    }
+}
+
+/// Whether `flags` is a well-formed [ProtocolFlags] value (as created by constructors in [new]).
+///
+/// Without `flags` cargo feature, [ProtocolFlags] is a primitive, so an out-of-range value could be
+/// passed as `PF`. Checks of that are otherwise only debug assertions. So, validate your protocol
+/// at compile time:
+///
+/// ```
+/// use hash_injector::{ProtocolFlags, is_valid, new};
+///
+/// const MY_PF: ProtocolFlags = new::trusted::u64();
+/// const _: () = assert!(is_valid(MY_PF));
+/// ```
+#[must_use = "`is_valid` only checks the flags; act on the result (for example, assert it)"]
+pub const fn is_valid(flags: ProtocolFlags) -> bool {
+    #[cfg(not(feature = "flags"))]
+    {
+        flags_from_bits(flags).is_some()
+    }
+    #[cfg(feature = "flags")]
+    {
+        flags_from_bits(flags_to_bits(flags)).is_some()
+    }
+}
+
 /// Constructors of [ProtocolFlags].
 pub mod new {
     #[cfg(any(feature = "mx", feature = "ndd"))]
@@ -239,8 +564,9 @@ pub mod new {
 
             #[cfg(not(feature = "flags"))]
             use crate::flags::{
-                FLAGS_BIT_SIGNAL_FIRST, FLAGS_MASK_HASH_I64, FLAGS_MASK_HASH_I128,
-                FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128, FLAGS_MASK_VIA_U8S,
+                FLAGS_BIT_SIGNAL_FIRST, FLAGS_MASK_HASH_BYTES, FLAGS_MASK_HASH_I64,
+                FLAGS_MASK_HASH_I128, FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128,
+                FLAGS_MASK_VIA_U8S,
             };
 
             /// Flag constructor for protocols that
@@ -310,6 +636,23 @@ pub mod new {
                     signal_first: true,
                 }
             }
+
+            /// Flag constructor for protocols that
+            /// - signals with a dedicated u8 slice (via [`core::hash::Hasher::write`])
+            /// - sends hash via [core::hash::Hasher::write] (8 little-endian bytes of a `u64`)
+            /// - signals before it submits the hash.
+            pub const fn bytes() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_U8S | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_BYTES
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::U8s,
+                    hash_via: HashVia::Bytes,
+                    signal_first: true,
+                }
+            }
         }
 
         /// Constructors of [crate::ProtocolFlags] for protocols that
@@ -323,8 +666,8 @@ pub mod new {
 
             #[cfg(not(feature = "flags"))]
             use crate::flags::{
-                FLAGS_MASK_HASH_I64, FLAGS_MASK_HASH_I128, FLAGS_MASK_HASH_U64,
-                FLAGS_MASK_HASH_U128, FLAGS_MASK_VIA_U8S,
+                FLAGS_MASK_HASH_BYTES, FLAGS_MASK_HASH_I64, FLAGS_MASK_HASH_I128,
+                FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128, FLAGS_MASK_VIA_U8S,
             };
 
             /// Flag constructor for protocols that
@@ -363,7 +706,7 @@ pub mod new {
 
             /// Flag constructor for protocols that
             /// - signals with a dedicated u8 slice (via [`core::hash::Hasher::write`])
-            /// - sends hash via [core::hash::Hasher::write_u129]
+            /// - sends hash via [core::hash::Hasher::write_u128]
             /// - submits the hash before it signals.
             pub const fn u128() -> ProtocolFlags {
                 #[cfg(not(feature = "flags"))]
@@ -380,7 +723,7 @@ pub mod new {
 
             /// Flag constructor for protocols that
             /// - signals with a dedicated u8 slice (via [`core::hash::Hasher::write`])
-            /// - sends hash via [core::hash::Hasher::write_u129]
+            /// - sends hash via [core::hash::Hasher::write_i128]
             /// - submits the hash before it signals.
             pub const fn i128() -> ProtocolFlags {
                 #[cfg(not(feature = "flags"))]
@@ -394,6 +737,23 @@ pub mod new {
                     signal_first: false,
                 }
             }
+
+            /// Flag constructor for protocols that
+            /// - signals with a dedicated u8 slice (via [`core::hash::Hasher::write`])
+            /// - sends hash via [core::hash::Hasher::write] (8 little-endian bytes of a `u64`)
+            /// - submits the hash before it signals.
+            pub const fn bytes() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_U8S | 0 | FLAGS_MASK_HASH_BYTES
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::U8s,
+                    hash_via: HashVia::Bytes,
+                    signal_first: false,
+                }
+            }
         }
     }
 
@@ -412,8 +772,9 @@ pub mod new {
 
             #[cfg(not(feature = "flags"))]
             use crate::flags::{
-                FLAGS_BIT_SIGNAL_FIRST, FLAGS_MASK_HASH_I64, FLAGS_MASK_HASH_I128,
-                FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128, FLAGS_MASK_VIA_LEN,
+                FLAGS_BIT_SIGNAL_FIRST, FLAGS_MASK_HASH_BYTES, FLAGS_MASK_HASH_I64,
+                FLAGS_MASK_HASH_I128, FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128,
+                FLAGS_MASK_VIA_LEN,
             };
 
             /// Flag constructor for protocols that
@@ -484,6 +845,23 @@ pub mod new {
                     signal_first: true,
                 }
             }
+
+            /// Flag constructor for protocols that
+            /// - signals with a fictitious length (via [`Hasher::write_length_prefix`]).
+            /// - sends hash via [core::hash::Hasher::write] (8 little-endian bytes of a `u64`)
+            /// - signals before it submits the hash.
+            pub const fn bytes() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_LEN | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_BYTES
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Len,
+                    hash_via: HashVia::Bytes,
+                    signal_first: true,
+                }
+            }
         }
 
         /// Constructors of [crate::ProtocolFlags] for protocols that that
@@ -497,8 +875,8 @@ pub mod new {
 
             #[cfg(not(feature = "flags"))]
             use crate::flags::{
-                FLAGS_MASK_HASH_I64, FLAGS_MASK_HASH_I128, FLAGS_MASK_HASH_U64,
-                FLAGS_MASK_HASH_U128, FLAGS_MASK_VIA_LEN,
+                FLAGS_MASK_HASH_BYTES, FLAGS_MASK_HASH_I64, FLAGS_MASK_HASH_I128,
+                FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128, FLAGS_MASK_VIA_LEN,
             };
 
             /// Flag constructor for protocols that
@@ -538,7 +916,7 @@ pub mod new {
 
             /// Flag constructor for protocols that
             /// - signals with a fictitious length (via [`core::hash::Hasher::write_length_prefix`]).
-            /// - sends hash via [core::hash::Hasher::write_u129]
+            /// - sends hash via [core::hash::Hasher::write_u128]
             /// - submits the hash before it signals.
             pub const fn u128() -> ProtocolFlags {
                 #[cfg(not(feature = "flags"))]
@@ -555,7 +933,7 @@ pub mod new {
 
             /// Flag constructor for protocols that
             /// - signals with a fictitious length (via [`Hasher::write_length_prefix`]).
-            /// - sends hash via [core::hash::Hasher::write_u129]
+            /// - sends hash via [core::hash::Hasher::write_i128]
             /// - submits the hash before it signals.
             pub const fn i128() -> ProtocolFlags {
                 #[cfg(not(feature = "flags"))]
@@ -569,15 +947,34 @@ pub mod new {
                     signal_first: false,
                 }
             }
+
+            /// Flag constructor for protocols that
+            /// - signals with a fictitious length (via [`Hasher::write_length_prefix`]).
+            /// - sends hash via [core::hash::Hasher::write] (8 little-endian bytes of a `u64`)
+            /// - submits the hash before it signals.
+            pub const fn bytes() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_LEN | 0 | FLAGS_MASK_HASH_BYTES
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Len,
+                    hash_via: HashVia::Bytes,
+                    signal_first: false,
+                }
+            }
         }
     }
 
-    #[cfg(any(feature = "mx", feature = "ndd"))]
-    /// Constructors of [crate::ProtocolFlags] for protocols that signal with a dedicated string
-    /// slice (via [`core::hash::Hasher::write_str`]).
-    pub mod str {
-        /// Flag constructor for protocols that
-        /// - signal with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+    #[cfg(feature = "hpe")]
+    /// Constructors of [crate::ProtocolFlags] for protocols that signal with a zero length (via
+    /// [`core::hash::Hasher::write_length_prefix`]) immediately followed by a cookie (via
+    /// [`core::hash::Hasher::write_u64`]). Unlike [len], a genuine length can't be mistaken for a
+    /// signal: A zero length is a signal only if the cookie follows.
+    pub mod len_cookie {
+        /// Constructors of [crate::ProtocolFlags] for protocols that that
+        /// - signal with a zero length followed by a cookie.
         /// - signal before they submit the hash.
         pub mod signal_first {
             use crate::flags::ProtocolFlags;
@@ -587,81 +984,99 @@ pub mod new {
 
             #[cfg(not(feature = "flags"))]
             use crate::flags::{
-                FLAGS_BIT_SIGNAL_FIRST, FLAGS_MASK_HASH_I64, FLAGS_MASK_HASH_I128,
-                FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128, FLAGS_MASK_VIA_STR,
+                FLAGS_BIT_SIGNAL_FIRST, FLAGS_MASK_HASH_BYTES, FLAGS_MASK_HASH_I64,
+                FLAGS_MASK_HASH_I128, FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128,
+                FLAGS_MASK_VIA_LEN_COOKIE,
             };
 
             /// Flag constructor for protocols that
-            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - signals with a zero length followed by a cookie
             /// - sends hash via [core::hash::Hasher::write_u64]
             /// - signals before it submits the hash.
             pub const fn u64() -> ProtocolFlags {
                 #[cfg(not(feature = "flags"))]
                 {
-                    FLAGS_MASK_VIA_STR | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_U64
+                    FLAGS_MASK_VIA_LEN_COOKIE | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_U64
                 }
                 #[cfg(feature = "flags")]
                 ProtocolFlags {
-                    signal_via: SignalVia::Str,
+                    signal_via: SignalVia::LenCookie,
                     hash_via: HashVia::U64,
                     signal_first: true,
                 }
             }
 
             /// Flag constructor for protocols that
-            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
-            /// - sends hash via [core::hash::Hasher::write_u64]
+            /// - signals with a zero length followed by a cookie
+            /// - sends hash via [core::hash::Hasher::write_i64]
             /// - signals before it submits the hash.
             pub const fn i64() -> ProtocolFlags {
                 #[cfg(not(feature = "flags"))]
                 {
-                    FLAGS_MASK_VIA_STR | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_I64
+                    FLAGS_MASK_VIA_LEN_COOKIE | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_I64
                 }
                 #[cfg(feature = "flags")]
                 ProtocolFlags {
-                    signal_via: SignalVia::Str,
+                    signal_via: SignalVia::LenCookie,
                     hash_via: HashVia::I64,
                     signal_first: true,
                 }
             }
 
             /// Flag constructor for protocols that
-            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - signals with a zero length followed by a cookie
             /// - sends hash via [core::hash::Hasher::write_u128]
             /// - signals before it submits the hash.
             pub const fn u128() -> ProtocolFlags {
                 #[cfg(not(feature = "flags"))]
                 {
-                    FLAGS_MASK_VIA_STR | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_U128
+                    FLAGS_MASK_VIA_LEN_COOKIE | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_U128
                 }
                 #[cfg(feature = "flags")]
                 ProtocolFlags {
-                    signal_via: SignalVia::Str,
+                    signal_via: SignalVia::LenCookie,
                     hash_via: HashVia::U128,
                     signal_first: true,
                 }
             }
 
             /// Flag constructor for protocols that
-            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - signals with a zero length followed by a cookie
             /// - sends hash via [core::hash::Hasher::write_i128]
             /// - signals before it submits the hash.
             pub const fn i128() -> ProtocolFlags {
                 #[cfg(not(feature = "flags"))]
                 {
-                    FLAGS_MASK_VIA_STR | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_I128
+                    FLAGS_MASK_VIA_LEN_COOKIE | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_I128
                 }
                 #[cfg(feature = "flags")]
                 ProtocolFlags {
-                    signal_via: SignalVia::Str,
+                    signal_via: SignalVia::LenCookie,
                     hash_via: HashVia::I128,
                     signal_first: true,
                 }
             }
+
+            /// Flag constructor for protocols that
+            /// - signals with a zero length followed by a cookie
+            /// - sends hash via [core::hash::Hasher::write] (8 little-endian bytes of a `u64`)
+            /// - signals before it submits the hash.
+            pub const fn bytes() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_LEN_COOKIE | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_BYTES
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::LenCookie,
+                    hash_via: HashVia::Bytes,
+                    signal_first: true,
+                }
+            }
         }
 
-        /// Flag constructor for protocols that
-        /// - signal with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+        /// Constructors of [crate::ProtocolFlags] for protocols that that
+        /// - signal with a zero length followed by a cookie.
         /// - submit the hash before they signal.
         pub mod submit_first {
             use crate::flags::ProtocolFlags;
@@ -671,96 +1086,1134 @@ pub mod new {
 
             #[cfg(not(feature = "flags"))]
             use crate::flags::{
-                FLAGS_MASK_HASH_I64, FLAGS_MASK_HASH_I128, FLAGS_MASK_HASH_U64,
-                FLAGS_MASK_HASH_U128, FLAGS_MASK_VIA_STR,
+                FLAGS_MASK_HASH_BYTES, FLAGS_MASK_HASH_I64, FLAGS_MASK_HASH_I128,
+                FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128, FLAGS_MASK_VIA_LEN_COOKIE,
             };
 
             /// Flag constructor for protocols that
-            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - signals with a zero length followed by a cookie
             /// - sends hash via [core::hash::Hasher::write_u64]
             /// - submits the hash before it signals.
             pub const fn u64() -> ProtocolFlags {
                 #[cfg(not(feature = "flags"))]
                 {
-                    FLAGS_MASK_VIA_STR | 0 | FLAGS_MASK_HASH_U64
+                    FLAGS_MASK_VIA_LEN_COOKIE | 0 | FLAGS_MASK_HASH_U64
                 }
                 #[cfg(feature = "flags")]
                 ProtocolFlags {
-                    signal_via: SignalVia::Str,
+                    signal_via: SignalVia::LenCookie,
                     hash_via: HashVia::U64,
                     signal_first: false,
                 }
             }
 
             /// Flag constructor for protocols that
-            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - signals with a zero length followed by a cookie
             /// - sends hash via [core::hash::Hasher::write_i64]
             /// - submits the hash before it signals.
             pub const fn i64() -> ProtocolFlags {
                 #[cfg(not(feature = "flags"))]
                 {
-                    FLAGS_MASK_VIA_STR | 0 | FLAGS_MASK_HASH_I64
+                    FLAGS_MASK_VIA_LEN_COOKIE | 0 | FLAGS_MASK_HASH_I64
                 }
                 #[cfg(feature = "flags")]
                 ProtocolFlags {
-                    signal_via: SignalVia::Str,
+                    signal_via: SignalVia::LenCookie,
                     hash_via: HashVia::I64,
                     signal_first: false,
                 }
             }
 
             /// Flag constructor for protocols that
-            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - signals with a zero length followed by a cookie
             /// - sends hash via [core::hash::Hasher::write_u128]
             /// - submits the hash before it signals.
             pub const fn u128() -> ProtocolFlags {
                 #[cfg(not(feature = "flags"))]
                 {
-                    FLAGS_MASK_VIA_STR | 0 | FLAGS_MASK_HASH_U128
+                    FLAGS_MASK_VIA_LEN_COOKIE | 0 | FLAGS_MASK_HASH_U128
                 }
                 #[cfg(feature = "flags")]
                 ProtocolFlags {
-                    signal_via: SignalVia::Str,
+                    signal_via: SignalVia::LenCookie,
                     hash_via: HashVia::U128,
                     signal_first: false,
                 }
             }
 
             /// Flag constructor for protocols that
-            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - signals with a zero length followed by a cookie
             /// - sends hash via [core::hash::Hasher::write_i128]
             /// - submits the hash before it signals.
             pub const fn i128() -> ProtocolFlags {
                 #[cfg(not(feature = "flags"))]
                 {
-                    FLAGS_MASK_VIA_STR | 0 | FLAGS_MASK_HASH_I128
+                    FLAGS_MASK_VIA_LEN_COOKIE | 0 | FLAGS_MASK_HASH_I128
                 }
                 #[cfg(feature = "flags")]
                 ProtocolFlags {
-                    signal_via: SignalVia::Str,
+                    signal_via: SignalVia::LenCookie,
                     hash_via: HashVia::I128,
                     signal_first: false,
                 }
             }
+
+            /// Flag constructor for protocols that
+            /// - signals with a zero length followed by a cookie
+            /// - sends hash via [core::hash::Hasher::write] (8 little-endian bytes of a `u64`)
+            /// - submits the hash before it signals.
+            pub const fn bytes() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_LEN_COOKIE | 0 | FLAGS_MASK_HASH_BYTES
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::LenCookie,
+                    hash_via: HashVia::Bytes,
+                    signal_first: false,
+                }
+            }
         }
     }
-}
-
-const _CHECKS: () = {
-    #[cfg(any(feature = "mx", feature = "ndd"))]
-    {
-        assert!(is_signal_via_u8s(new::u8s::signal_first::u64()) == true);
-        assert!(is_signal_via_u8s(new::u8s::signal_first::i64()) == true);
-        assert!(is_signal_via_u8s(new::u8s::signal_first::u128()) == true);
-        assert!(is_signal_via_u8s(new::u8s::signal_first::i128()) == true);
 
-        assert!(is_signal_via_u8s(new::u8s::submit_first::u64()) == true);
-        assert!(is_signal_via_u8s(new::u8s::submit_first::i64()) == true);
-        assert!(is_signal_via_u8s(new::u8s::submit_first::u128()) == true);
-        assert!(is_signal_via_u8s(new::u8s::submit_first::i128()) == true);
-    }
     #[cfg(feature = "hpe")]
-    {
+    /// Constructor of [crate::ProtocolFlags] for the protocol that signals with a fictitious length
+    /// (see [crate::signals::len_signal_hash]), and then carries the hash in two more lengths: its
+    /// high and low 32 bits. All via [`core::hash::Hasher::write_length_prefix`] - so this is the
+    /// protocol for [core::hash::Hasher]s that specialize only `write_length_prefix`.
+    ///
+    /// It signals first. Each half fits in `usize` on 32-bit targets, too.
+    pub mod len_carrier {
+        use crate::flags::ProtocolFlags;
+
+        #[cfg(feature = "flags")]
+        use crate::flags::{HashVia, SignalVia};
+
+        #[cfg(not(feature = "flags"))]
+        use crate::flags::{
+            FLAGS_BIT_SIGNAL_FIRST, FLAGS_MASK_HASH_U64, FLAGS_MASK_VIA_LEN_CARRIER,
+        };
+
+        /// Flag constructor for the protocol that
+        /// - signals with a fictitious length
+        /// - carries the (u64) hash in two more lengths (both via
+        ///   [core::hash::Hasher::write_length_prefix])
+        /// - signals before it submits the hash.
+        pub const fn u64() -> ProtocolFlags {
+            #[cfg(not(feature = "flags"))]
+            {
+                FLAGS_MASK_VIA_LEN_CARRIER | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_U64
+            }
+            #[cfg(feature = "flags")]
+            ProtocolFlags {
+                signal_via: SignalVia::LenCarrier,
+                hash_via: HashVia::U64,
+                signal_first: true,
+            }
+        }
+    }
+
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    /// Constructors of [crate::ProtocolFlags] for protocols that signal with a dedicated string
+    /// slice (via [`core::hash::Hasher::write_str`]).
+    pub mod str {
+        /// Flag constructor for protocols that
+        /// - signal with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+        /// - signal before they submit the hash.
+        pub mod signal_first {
+            use crate::flags::ProtocolFlags;
+
+            #[cfg(feature = "flags")]
+            use crate::flags::{HashVia, SignalVia};
+
+            #[cfg(not(feature = "flags"))]
+            use crate::flags::{
+                FLAGS_BIT_SIGNAL_FIRST, FLAGS_MASK_HASH_BYTES, FLAGS_MASK_HASH_I64,
+                FLAGS_MASK_HASH_I128, FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128,
+                FLAGS_MASK_VIA_STR,
+            };
+
+            /// Flag constructor for protocols that
+            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - sends hash via [core::hash::Hasher::write_u64]
+            /// - signals before it submits the hash.
+            pub const fn u64() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_STR | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_U64
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Str,
+                    hash_via: HashVia::U64,
+                    signal_first: true,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - sends hash via [core::hash::Hasher::write_u64]
+            /// - signals before it submits the hash.
+            pub const fn i64() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_STR | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_I64
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Str,
+                    hash_via: HashVia::I64,
+                    signal_first: true,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - sends hash via [core::hash::Hasher::write_u128]
+            /// - signals before it submits the hash.
+            pub const fn u128() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_STR | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_U128
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Str,
+                    hash_via: HashVia::U128,
+                    signal_first: true,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - sends hash via [core::hash::Hasher::write_i128]
+            /// - signals before it submits the hash.
+            pub const fn i128() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_STR | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_I128
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Str,
+                    hash_via: HashVia::I128,
+                    signal_first: true,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - sends hash via [core::hash::Hasher::write] (8 little-endian bytes of a `u64`)
+            /// - signals before it submits the hash.
+            pub const fn bytes() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_STR | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_BYTES
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Str,
+                    hash_via: HashVia::Bytes,
+                    signal_first: true,
+                }
+            }
+        }
+
+        /// Flag constructor for protocols that
+        /// - signal with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+        /// - submit the hash before they signal.
+        pub mod submit_first {
+            use crate::flags::ProtocolFlags;
+
+            #[cfg(feature = "flags")]
+            use crate::flags::{HashVia, SignalVia};
+
+            #[cfg(not(feature = "flags"))]
+            use crate::flags::{
+                FLAGS_MASK_HASH_BYTES, FLAGS_MASK_HASH_I64, FLAGS_MASK_HASH_I128,
+                FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128, FLAGS_MASK_VIA_STR,
+            };
+
+            /// Flag constructor for protocols that
+            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - sends hash via [core::hash::Hasher::write_u64]
+            /// - submits the hash before it signals.
+            pub const fn u64() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_STR | 0 | FLAGS_MASK_HASH_U64
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Str,
+                    hash_via: HashVia::U64,
+                    signal_first: false,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - sends hash via [core::hash::Hasher::write_i64]
+            /// - submits the hash before it signals.
+            pub const fn i64() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_STR | 0 | FLAGS_MASK_HASH_I64
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Str,
+                    hash_via: HashVia::I64,
+                    signal_first: false,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - sends hash via [core::hash::Hasher::write_u128]
+            /// - submits the hash before it signals.
+            pub const fn u128() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_STR | 0 | FLAGS_MASK_HASH_U128
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Str,
+                    hash_via: HashVia::U128,
+                    signal_first: false,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - sends hash via [core::hash::Hasher::write_i128]
+            /// - submits the hash before it signals.
+            pub const fn i128() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_STR | 0 | FLAGS_MASK_HASH_I128
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Str,
+                    hash_via: HashVia::I128,
+                    signal_first: false,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a dedicated string slice (via [`core::hash::Hasher::write_str`]).
+            /// - sends hash via [core::hash::Hasher::write] (8 little-endian bytes of a `u64`)
+            /// - submits the hash before it signals.
+            pub const fn bytes() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_STR | 0 | FLAGS_MASK_HASH_BYTES
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Str,
+                    hash_via: HashVia::Bytes,
+                    signal_first: false,
+                }
+            }
+        }
+    }
+
+    /// Constructors of [crate::ProtocolFlags] for protocols that signal with a sequence of
+    /// [`core::hash::Hasher::write_u8`] calls.
+    pub mod u8_stream {
+        /// Constructors of [crate::ProtocolFlags] for protocols that that
+        /// - signal with a sequence of [`core::hash::Hasher::write_u8`] calls.
+        /// - signal before they submit the hash.
+        pub mod signal_first {
+            use crate::flags::ProtocolFlags;
+
+            #[cfg(feature = "flags")]
+            use crate::flags::{HashVia, SignalVia};
+
+            #[cfg(not(feature = "flags"))]
+            use crate::flags::{
+                FLAGS_BIT_SIGNAL_FIRST, FLAGS_MASK_HASH_BYTES, FLAGS_MASK_HASH_I64,
+                FLAGS_MASK_HASH_I128, FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128,
+                FLAGS_MASK_VIA_U8_STREAM,
+            };
+
+            /// Flag constructor for protocols that
+            /// - signals with a sequence of [`core::hash::Hasher::write_u8`] calls
+            /// - sends hash via [core::hash::Hasher::write_u64]
+            /// - signals before it submits the hash.
+            pub const fn u64() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_U8_STREAM | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_U64
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::U8Stream,
+                    hash_via: HashVia::U64,
+                    signal_first: true,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a sequence of [`core::hash::Hasher::write_u8`] calls
+            /// - sends hash via [core::hash::Hasher::write_i64]
+            /// - signals before it submits the hash.
+            pub const fn i64() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_U8_STREAM | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_I64
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::U8Stream,
+                    hash_via: HashVia::I64,
+                    signal_first: true,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a sequence of [`core::hash::Hasher::write_u8`] calls
+            /// - sends hash via [core::hash::Hasher::write_u128]
+            /// - signals before it submits the hash.
+            pub const fn u128() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_U8_STREAM | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_U128
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::U8Stream,
+                    hash_via: HashVia::U128,
+                    signal_first: true,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a sequence of [`core::hash::Hasher::write_u8`] calls
+            /// - sends hash via [core::hash::Hasher::write_u128]
+            /// - signals before it submits the hash.
+            pub const fn i128() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_U8_STREAM | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_I128
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::U8Stream,
+                    hash_via: HashVia::I128,
+                    signal_first: true,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a sequence of [`core::hash::Hasher::write_u8`] calls
+            /// - sends hash via [core::hash::Hasher::write] (8 little-endian bytes of a `u64`)
+            /// - signals before it submits the hash.
+            pub const fn bytes() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_U8_STREAM | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_BYTES
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::U8Stream,
+                    hash_via: HashVia::Bytes,
+                    signal_first: true,
+                }
+            }
+        }
+
+        /// Constructors of [crate::ProtocolFlags] for protocols that that
+        /// - signal with a sequence of [`core::hash::Hasher::write_u8`] calls.
+        /// - submit the hash before they signal.
+        pub mod submit_first {
+            use crate::flags::ProtocolFlags;
+
+            #[cfg(feature = "flags")]
+            use crate::flags::{HashVia, SignalVia};
+
+            #[cfg(not(feature = "flags"))]
+            use crate::flags::{
+                FLAGS_MASK_HASH_BYTES, FLAGS_MASK_HASH_I64, FLAGS_MASK_HASH_I128,
+                FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128, FLAGS_MASK_VIA_U8_STREAM,
+            };
+
+            /// Flag constructor for protocols that
+            /// - signals with a sequence of [`core::hash::Hasher::write_u8`] calls
+            /// - sends hash via [core::hash::Hasher::write_u64]
+            /// - submits the hash before it signals.
+            pub const fn u64() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_U8_STREAM | FLAGS_MASK_HASH_U64
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::U8Stream,
+                    hash_via: HashVia::U64,
+                    signal_first: false,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a sequence of [`core::hash::Hasher::write_u8`] calls
+            /// - sends hash via [core::hash::Hasher::write_i64]
+            /// - submits the hash before it signals.
+            pub const fn i64() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_U8_STREAM | FLAGS_MASK_HASH_I64
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::U8Stream,
+                    hash_via: HashVia::I64,
+                    signal_first: false,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a sequence of [`core::hash::Hasher::write_u8`] calls
+            /// - sends hash via [core::hash::Hasher::write_u128]
+            /// - submits the hash before it signals.
+            pub const fn u128() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_U8_STREAM | FLAGS_MASK_HASH_U128
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::U8Stream,
+                    hash_via: HashVia::U128,
+                    signal_first: false,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a sequence of [`core::hash::Hasher::write_u8`] calls
+            /// - sends hash via [core::hash::Hasher::write_i128]
+            /// - submits the hash before it signals.
+            pub const fn i128() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_U8_STREAM | FLAGS_MASK_HASH_I128
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::U8Stream,
+                    hash_via: HashVia::I128,
+                    signal_first: false,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with a sequence of [`core::hash::Hasher::write_u8`] calls
+            /// - sends hash via [core::hash::Hasher::write] (8 little-endian bytes of a `u64`)
+            /// - submits the hash before it signals.
+            pub const fn bytes() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_U8_STREAM | FLAGS_MASK_HASH_BYTES
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::U8Stream,
+                    hash_via: HashVia::Bytes,
+                    signal_first: false,
+                }
+            }
+        }
+    }
+
+    /// Constructors of [crate::ProtocolFlags] for protocols that signal with `usize::MAX` (via
+    /// [`core::hash::Hasher::write_usize`]) immediately followed by a cookie (via
+    /// [`core::hash::Hasher::write_u64`]). Like [len_cookie], but these don't need `hpe` feature
+    /// (nor nightly).
+    ///
+    /// Many [core::hash::Hash] implementations write collection lengths with `write_usize` (and
+    /// without `hpe`, so does `write_length_prefix`). Hence the cookie: `usize::MAX` alone is
+    /// ordinary data. Only a key that writes `usize::MAX` and then the cookie (as `u64`) would be
+    /// mistaken for one that injects.
+    pub mod usize {
+        /// Constructors of [crate::ProtocolFlags] for protocols that that
+        /// - signal with `usize::MAX` followed by a cookie.
+        /// - signal before they submit the hash.
+        pub mod signal_first {
+            use crate::flags::ProtocolFlags;
+
+            #[cfg(feature = "flags")]
+            use crate::flags::{HashVia, SignalVia};
+
+            #[cfg(not(feature = "flags"))]
+            use crate::flags::{
+                FLAGS_BIT_SIGNAL_FIRST, FLAGS_MASK_HASH_BYTES, FLAGS_MASK_HASH_I64,
+                FLAGS_MASK_HASH_I128, FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128,
+                FLAGS_MASK_VIA_USIZE,
+            };
+
+            /// Flag constructor for protocols that
+            /// - signals with `usize::MAX` followed by a cookie
+            /// - sends hash via [core::hash::Hasher::write_u64]
+            /// - signals before it submits the hash.
+            pub const fn u64() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_USIZE | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_U64
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Usize,
+                    hash_via: HashVia::U64,
+                    signal_first: true,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with `usize::MAX` followed by a cookie
+            /// - sends hash via [core::hash::Hasher::write_i64]
+            /// - signals before it submits the hash.
+            pub const fn i64() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_USIZE | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_I64
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Usize,
+                    hash_via: HashVia::I64,
+                    signal_first: true,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with `usize::MAX` followed by a cookie
+            /// - sends hash via [core::hash::Hasher::write_u128]
+            /// - signals before it submits the hash.
+            pub const fn u128() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_USIZE | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_U128
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Usize,
+                    hash_via: HashVia::U128,
+                    signal_first: true,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with `usize::MAX` followed by a cookie
+            /// - sends hash via [core::hash::Hasher::write_i128]
+            /// - signals before it submits the hash.
+            pub const fn i128() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_USIZE | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_I128
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Usize,
+                    hash_via: HashVia::I128,
+                    signal_first: true,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with `usize::MAX` followed by a cookie
+            /// - sends hash via [core::hash::Hasher::write] (8 little-endian bytes of a `u64`)
+            /// - signals before it submits the hash.
+            pub const fn bytes() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_USIZE | FLAGS_BIT_SIGNAL_FIRST | FLAGS_MASK_HASH_BYTES
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Usize,
+                    hash_via: HashVia::Bytes,
+                    signal_first: true,
+                }
+            }
+        }
+
+        /// Constructors of [crate::ProtocolFlags] for protocols that that
+        /// - signal with `usize::MAX` followed by a cookie.
+        /// - submit the hash before they signal.
+        pub mod submit_first {
+            use crate::flags::ProtocolFlags;
+
+            #[cfg(feature = "flags")]
+            use crate::flags::{HashVia, SignalVia};
+
+            #[cfg(not(feature = "flags"))]
+            use crate::flags::{
+                FLAGS_MASK_HASH_BYTES, FLAGS_MASK_HASH_I64, FLAGS_MASK_HASH_I128,
+                FLAGS_MASK_HASH_U64, FLAGS_MASK_HASH_U128, FLAGS_MASK_VIA_USIZE,
+            };
+
+            /// Flag constructor for protocols that
+            /// - signals with `usize::MAX` followed by a cookie
+            /// - sends hash via [core::hash::Hasher::write_u64]
+            /// - submits the hash before it signals.
+            pub const fn u64() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_USIZE | FLAGS_MASK_HASH_U64
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Usize,
+                    hash_via: HashVia::U64,
+                    signal_first: false,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with `usize::MAX` followed by a cookie
+            /// - sends hash via [core::hash::Hasher::write_i64]
+            /// - submits the hash before it signals.
+            pub const fn i64() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_USIZE | FLAGS_MASK_HASH_I64
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Usize,
+                    hash_via: HashVia::I64,
+                    signal_first: false,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with `usize::MAX` followed by a cookie
+            /// - sends hash via [core::hash::Hasher::write_u128]
+            /// - submits the hash before it signals.
+            pub const fn u128() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_USIZE | FLAGS_MASK_HASH_U128
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Usize,
+                    hash_via: HashVia::U128,
+                    signal_first: false,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with `usize::MAX` followed by a cookie
+            /// - sends hash via [core::hash::Hasher::write_i128]
+            /// - submits the hash before it signals.
+            pub const fn i128() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_USIZE | FLAGS_MASK_HASH_I128
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Usize,
+                    hash_via: HashVia::I128,
+                    signal_first: false,
+                }
+            }
+
+            /// Flag constructor for protocols that
+            /// - signals with `usize::MAX` followed by a cookie
+            /// - sends hash via [core::hash::Hasher::write] (8 little-endian bytes of a `u64`)
+            /// - submits the hash before it signals.
+            pub const fn bytes() -> ProtocolFlags {
+                #[cfg(not(feature = "flags"))]
+                {
+                    FLAGS_MASK_VIA_USIZE | FLAGS_MASK_HASH_BYTES
+                }
+                #[cfg(feature = "flags")]
+                ProtocolFlags {
+                    signal_via: SignalVia::Usize,
+                    hash_via: HashVia::Bytes,
+                    signal_first: false,
+                }
+            }
+        }
+    }
+
+    /// Constructors of [crate::ProtocolFlags] for protocols that do NOT signal at all. The first
+    /// (and only) write of the hash (through the respective `Hasher::write_xxx`) IS the injected
+    /// hash.
+    ///
+    /// Use ONLY when the [core::hash::BuildHasher] is guaranteed to be
+    /// [crate::SignalledInjectionBuildHasher], and ONLY for keys that inject their hashes. Do NOT
+    /// mix with ordinary hashing: An ordinary key whose first write is of the same type as the hash
+    /// would be mistaken for an injected hash.
+    pub mod trusted {
+        use crate::flags::ProtocolFlags;
+
+        #[cfg(feature = "flags")]
+        use crate::flags::{HashVia, SignalVia};
+
+        #[cfg(not(feature = "flags"))]
+        use crate::flags::{
+            FLAGS_MASK_HASH_BYTES, FLAGS_MASK_HASH_I64, FLAGS_MASK_HASH_I128, FLAGS_MASK_HASH_U64,
+            FLAGS_MASK_HASH_U128, FLAGS_MASK_VIA_TRUSTED,
+        };
+
+        /// Flag constructor for protocols that
+        /// - do NOT signal
+        /// - send hash via [core::hash::Hasher::write_u64].
+        pub const fn u64() -> ProtocolFlags {
+            #[cfg(not(feature = "flags"))]
+            {
+                FLAGS_MASK_VIA_TRUSTED | FLAGS_MASK_HASH_U64
+            }
+            #[cfg(feature = "flags")]
+            ProtocolFlags {
+                signal_via: SignalVia::Trusted,
+                hash_via: HashVia::U64,
+                signal_first: false,
+            }
+        }
+
+        /// Flag constructor for protocols that
+        /// - do NOT signal
+        /// - send hash via [core::hash::Hasher::write_i64].
+        pub const fn i64() -> ProtocolFlags {
+            #[cfg(not(feature = "flags"))]
+            {
+                FLAGS_MASK_VIA_TRUSTED | FLAGS_MASK_HASH_I64
+            }
+            #[cfg(feature = "flags")]
+            ProtocolFlags {
+                signal_via: SignalVia::Trusted,
+                hash_via: HashVia::I64,
+                signal_first: false,
+            }
+        }
+
+        /// Flag constructor for protocols that
+        /// - do NOT signal
+        /// - send hash via [core::hash::Hasher::write_u128].
+        pub const fn u128() -> ProtocolFlags {
+            #[cfg(not(feature = "flags"))]
+            {
+                FLAGS_MASK_VIA_TRUSTED | FLAGS_MASK_HASH_U128
+            }
+            #[cfg(feature = "flags")]
+            ProtocolFlags {
+                signal_via: SignalVia::Trusted,
+                hash_via: HashVia::U128,
+                signal_first: false,
+            }
+        }
+
+        /// Flag constructor for protocols that
+        /// - do NOT signal
+        /// - send hash via [core::hash::Hasher::write_i128].
+        pub const fn i128() -> ProtocolFlags {
+            #[cfg(not(feature = "flags"))]
+            {
+                FLAGS_MASK_VIA_TRUSTED | FLAGS_MASK_HASH_I128
+            }
+            #[cfg(feature = "flags")]
+            ProtocolFlags {
+                signal_via: SignalVia::Trusted,
+                hash_via: HashVia::I128,
+                signal_first: false,
+            }
+        }
+
+        /// Flag constructor for protocols that
+        /// - do NOT signal
+        /// - send hash via [core::hash::Hasher::write] (8 little-endian bytes of a `u64`).
+        pub const fn bytes() -> ProtocolFlags {
+            #[cfg(not(feature = "flags"))]
+            {
+                FLAGS_MASK_VIA_TRUSTED | FLAGS_MASK_HASH_BYTES
+            }
+            #[cfg(feature = "flags")]
+            ProtocolFlags {
+                signal_via: SignalVia::Trusted,
+                hash_via: HashVia::Bytes,
+                signal_first: false,
+            }
+        }
+    }
+}
+
+/// All protocols (see [new]) that are enabled by the current cargo features, for example, for tests
+/// that iterate over all of them.
+pub const ALL_PROTOCOLS: &[ProtocolFlags] = &[
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    new::u8s::signal_first::u64(),
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    new::u8s::signal_first::i64(),
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    new::u8s::signal_first::u128(),
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    new::u8s::signal_first::i128(),
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    new::u8s::signal_first::bytes(),
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    new::u8s::submit_first::u64(),
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    new::u8s::submit_first::i64(),
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    new::u8s::submit_first::u128(),
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    new::u8s::submit_first::i128(),
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    new::u8s::submit_first::bytes(),
+    #[cfg(feature = "hpe")]
+    new::len::signal_first::u64(),
+    #[cfg(feature = "hpe")]
+    new::len::signal_first::i64(),
+    #[cfg(feature = "hpe")]
+    new::len::signal_first::u128(),
+    #[cfg(feature = "hpe")]
+    new::len::signal_first::i128(),
+    #[cfg(feature = "hpe")]
+    new::len::signal_first::bytes(),
+    #[cfg(feature = "hpe")]
+    new::len::submit_first::u64(),
+    #[cfg(feature = "hpe")]
+    new::len::submit_first::i64(),
+    #[cfg(feature = "hpe")]
+    new::len::submit_first::u128(),
+    #[cfg(feature = "hpe")]
+    new::len::submit_first::i128(),
+    #[cfg(feature = "hpe")]
+    new::len::submit_first::bytes(),
+    #[cfg(feature = "hpe")]
+    new::len_cookie::signal_first::u64(),
+    #[cfg(feature = "hpe")]
+    new::len_cookie::signal_first::i64(),
+    #[cfg(feature = "hpe")]
+    new::len_cookie::signal_first::u128(),
+    #[cfg(feature = "hpe")]
+    new::len_cookie::signal_first::i128(),
+    #[cfg(feature = "hpe")]
+    new::len_cookie::signal_first::bytes(),
+    #[cfg(feature = "hpe")]
+    new::len_cookie::submit_first::u64(),
+    #[cfg(feature = "hpe")]
+    new::len_cookie::submit_first::i64(),
+    #[cfg(feature = "hpe")]
+    new::len_cookie::submit_first::u128(),
+    #[cfg(feature = "hpe")]
+    new::len_cookie::submit_first::i128(),
+    #[cfg(feature = "hpe")]
+    new::len_cookie::submit_first::bytes(),
+    #[cfg(feature = "hpe")]
+    new::len_carrier::u64(),
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    new::str::signal_first::u64(),
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    new::str::signal_first::i64(),
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    new::str::signal_first::u128(),
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    new::str::signal_first::i128(),
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    new::str::signal_first::bytes(),
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    new::str::submit_first::u64(),
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    new::str::submit_first::i64(),
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    new::str::submit_first::u128(),
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    new::str::submit_first::i128(),
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    new::str::submit_first::bytes(),
+    new::u8_stream::signal_first::u64(),
+    new::u8_stream::signal_first::i64(),
+    new::u8_stream::signal_first::u128(),
+    new::u8_stream::signal_first::i128(),
+    new::u8_stream::signal_first::bytes(),
+    new::u8_stream::submit_first::u64(),
+    new::u8_stream::submit_first::i64(),
+    new::u8_stream::submit_first::u128(),
+    new::u8_stream::submit_first::i128(),
+    new::u8_stream::submit_first::bytes(),
+    new::usize::signal_first::u64(),
+    new::usize::signal_first::i64(),
+    new::usize::signal_first::u128(),
+    new::usize::signal_first::i128(),
+    new::usize::signal_first::bytes(),
+    new::usize::submit_first::u64(),
+    new::usize::submit_first::i64(),
+    new::usize::submit_first::u128(),
+    new::usize::submit_first::i128(),
+    new::usize::submit_first::bytes(),
+    new::trusted::u64(),
+    new::trusted::i64(),
+    new::trusted::u128(),
+    new::trusted::i128(),
+    new::trusted::bytes(),
+];
+
+const _CHECKS: () = {
+    // Canonical bits. These must never change.
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    {
+        assert!(flags_to_bits(new::u8s::signal_first::u64()) == 0b0001000);
+        assert!(flags_to_bits(new::u8s::signal_first::i64()) == 0b0011000);
+        assert!(flags_to_bits(new::u8s::signal_first::u128()) == 0b0101000);
+        assert!(flags_to_bits(new::u8s::signal_first::i128()) == 0b0111000);
+        assert!(flags_to_bits(new::u8s::submit_first::u64()) == 0b0000000);
+        assert!(flags_to_bits(new::u8s::submit_first::i64()) == 0b0010000);
+        assert!(flags_to_bits(new::u8s::submit_first::u128()) == 0b0100000);
+        assert!(flags_to_bits(new::u8s::submit_first::i128()) == 0b0110000);
+        assert!(flags_to_bits(new::u8s::signal_first::bytes()) == 0b1001000);
+        assert!(flags_to_bits(new::u8s::submit_first::bytes()) == 0b1000000);
+        assert!(flags_to_bits(new::str::signal_first::bytes()) == 0b1001010);
+        assert!(flags_to_bits(new::str::submit_first::bytes()) == 0b1000010);
+    }
+    #[cfg(feature = "hpe")]
+    {
+        assert!(flags_to_bits(new::len::signal_first::u64()) == 0b0001001);
+        assert!(flags_to_bits(new::len::signal_first::i64()) == 0b0011001);
+        assert!(flags_to_bits(new::len::signal_first::u128()) == 0b0101001);
+        assert!(flags_to_bits(new::len::signal_first::i128()) == 0b0111001);
+        assert!(flags_to_bits(new::len::submit_first::u64()) == 0b0000001);
+        assert!(flags_to_bits(new::len::submit_first::i64()) == 0b0010001);
+        assert!(flags_to_bits(new::len::submit_first::u128()) == 0b0100001);
+        assert!(flags_to_bits(new::len::submit_first::i128()) == 0b0110001);
+        assert!(flags_to_bits(new::len::signal_first::bytes()) == 0b1001001);
+        assert!(flags_to_bits(new::len::submit_first::bytes()) == 0b1000001);
+        assert!(flags_to_bits(new::len_cookie::signal_first::bytes()) == 0b1001101);
+        assert!(flags_to_bits(new::len_cookie::submit_first::bytes()) == 0b1000101);
+        assert!(flags_to_bits(new::len_carrier::u64()) == 0b0001111);
+    }
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    {
+        assert!(flags_to_bits(new::str::signal_first::u64()) == 0b0001010);
+        assert!(flags_to_bits(new::str::signal_first::i64()) == 0b0011010);
+        assert!(flags_to_bits(new::str::signal_first::u128()) == 0b0101010);
+        assert!(flags_to_bits(new::str::signal_first::i128()) == 0b0111010);
+        assert!(flags_to_bits(new::str::submit_first::u64()) == 0b0000010);
+        assert!(flags_to_bits(new::str::submit_first::i64()) == 0b0010010);
+        assert!(flags_to_bits(new::str::submit_first::u128()) == 0b0100010);
+        assert!(flags_to_bits(new::str::submit_first::i128()) == 0b0110010);
+    }
+    assert!(flags_to_bits(new::u8_stream::signal_first::u64()) == 0b0001100);
+    assert!(flags_to_bits(new::u8_stream::signal_first::i64()) == 0b0011100);
+    assert!(flags_to_bits(new::u8_stream::signal_first::u128()) == 0b0101100);
+    assert!(flags_to_bits(new::u8_stream::signal_first::i128()) == 0b0111100);
+    assert!(flags_to_bits(new::u8_stream::submit_first::u64()) == 0b0000100);
+    assert!(flags_to_bits(new::u8_stream::submit_first::i64()) == 0b0010100);
+    assert!(flags_to_bits(new::u8_stream::submit_first::u128()) == 0b0100100);
+    assert!(flags_to_bits(new::u8_stream::submit_first::i128()) == 0b0110100);
+    #[cfg(feature = "hpe")]
+    {
+        assert!(flags_to_bits(new::len_cookie::signal_first::u64()) == 0b0001101);
+        assert!(flags_to_bits(new::len_cookie::signal_first::i64()) == 0b0011101);
+        assert!(flags_to_bits(new::len_cookie::signal_first::u128()) == 0b0101101);
+        assert!(flags_to_bits(new::len_cookie::signal_first::i128()) == 0b0111101);
+        assert!(flags_to_bits(new::len_cookie::submit_first::u64()) == 0b0000101);
+        assert!(flags_to_bits(new::len_cookie::submit_first::i64()) == 0b0010101);
+        assert!(flags_to_bits(new::len_cookie::submit_first::u128()) == 0b0100101);
+        assert!(flags_to_bits(new::len_cookie::submit_first::i128()) == 0b0110101);
+    }
+    assert!(flags_to_bits(new::usize::signal_first::u64()) == 0b0001110);
+    assert!(flags_to_bits(new::usize::signal_first::i64()) == 0b0011110);
+    assert!(flags_to_bits(new::usize::signal_first::u128()) == 0b0101110);
+    assert!(flags_to_bits(new::usize::signal_first::i128()) == 0b0111110);
+    assert!(flags_to_bits(new::usize::submit_first::u64()) == 0b0000110);
+    assert!(flags_to_bits(new::usize::submit_first::i64()) == 0b0010110);
+    assert!(flags_to_bits(new::usize::submit_first::u128()) == 0b0100110);
+    assert!(flags_to_bits(new::usize::submit_first::i128()) == 0b0110110);
+    assert!(flags_to_bits(new::trusted::u64()) == 0b0000011);
+    assert!(flags_to_bits(new::trusted::i64()) == 0b0010011);
+    assert!(flags_to_bits(new::trusted::u128()) == 0b0100011);
+    assert!(flags_to_bits(new::trusted::i128()) == 0b0110011);
+    assert!(flags_to_bits(new::trusted::bytes()) == 0b1000011);
+    assert!(flags_to_bits(new::u8_stream::signal_first::bytes()) == 0b1001100);
+    assert!(flags_to_bits(new::u8_stream::submit_first::bytes()) == 0b1000100);
+    assert!(flags_to_bits(new::usize::signal_first::bytes()) == 0b1001110);
+    assert!(flags_to_bits(new::usize::submit_first::bytes()) == 0b1000110);
+    assert!(matches!(flags_from_bits(0b011_0011), Some(f) if flags_to_bits(f) == 0b011_0011));
+    assert!(matches!(flags_from_bits(0b001_1001), Some(f) if flags_to_bits(f) == 0b001_1001));
+    assert!(flags_from_bits(0b000_0111).is_none());
+    assert!(flags_from_bits(0b001_1111).is_none());
+    assert!(matches!(flags_from_bits(0b000_1111), Some(f) if flags_to_bits(f) == 0b000_1111));
+    assert!(flags_from_bits(0b101_0000).is_none());
+    assert!(flags_from_bits(0b1000_0000).is_none());
+    // Trusted, but signal first:
+    assert!(flags_from_bits(0b000_1011).is_none());
+
+    assert!(is_valid(new::trusted::u64()));
+    assert!(is_valid(new::trusted::i128()));
+    assert!(is_valid(new::u8_stream::signal_first::u64()));
+    assert!(is_signal_via_u8_stream(new::u8_stream::submit_first::i128()));
+    assert!(!is_signal_via_u8_stream(new::trusted::u64()));
+    assert!(is_valid(new::usize::signal_first::u64()));
+    assert!(is_signal_via_usize(new::usize::submit_first::i128()));
+    assert!(!is_signal_via_usize(new::u8_stream::submit_first::i128()));
+    #[cfg(feature = "hpe")]
+    {
+        assert!(is_valid(new::len::signal_first::u64()));
+        assert!(is_valid(new::len::submit_first::i128()));
+        assert!(is_valid(new::len_cookie::signal_first::u64()));
+        assert!(is_signal_via_len_cookie(
+            new::len_cookie::submit_first::i128()
+        ));
+        assert!(!is_signal_via_len_cookie(new::len::submit_first::i128()));
+    }
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    {
+        assert!(is_valid(new::u8s::signal_first::u128()));
+        assert!(is_valid(new::str::submit_first::i64()));
+    }
+    #[cfg(not(feature = "flags"))]
+    {
+        assert!(!is_valid(0xFF));
+        assert!(!is_valid(0b000_0111));
+        // len_carrier signals first, and it carries a u64 only.
+        assert!(is_valid(0b000_1111));
+        assert!(!is_valid(0b001_1111));
+        assert!(!is_valid(0b111_0000));
+        assert!(!is_valid(0b101_0000));
+        assert!(!is_valid(0b000_1011));
+    }
+    // ----
+    assert!(is_signal_via_trusted(new::trusted::u64()));
+    assert!(is_signal_via_trusted(new::trusted::i64()));
+    assert!(is_signal_via_trusted(new::trusted::u128()));
+    assert!(is_signal_via_trusted(new::trusted::i128()));
+
+    assert!(is_hash_via_u64(new::trusted::u64()));
+    assert!(is_hash_via_i64(new::trusted::i64()));
+    assert!(is_hash_via_u128(new::trusted::u128()));
+    assert!(is_hash_via_i128(new::trusted::i128()));
+    assert!(is_hash_via_bytes(new::trusted::bytes()));
+    assert!(!is_hash_via_bytes(new::trusted::u64()));
+    assert!(hash_via_bytes(new::trusted::bytes()) == 8);
+
+    assert!(is_submit_first(new::trusted::u64()));
+    assert!(!is_signal_via_u8s(new::trusted::u64()));
+    assert!(!is_signal_via_len(new::trusted::u64()));
+    assert!(!is_signal_via_str(new::trusted::u64()));
+    // ----
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    {
+        assert!(is_signal_via_u8s(new::u8s::signal_first::u64()) == true);
+        assert!(is_signal_via_u8s(new::u8s::signal_first::i64()) == true);
+        assert!(is_signal_via_u8s(new::u8s::signal_first::u128()) == true);
+        assert!(is_signal_via_u8s(new::u8s::signal_first::i128()) == true);
+
+        assert!(is_signal_via_u8s(new::u8s::submit_first::u64()) == true);
+        assert!(is_signal_via_u8s(new::u8s::submit_first::i64()) == true);
+        assert!(is_signal_via_u8s(new::u8s::submit_first::u128()) == true);
+        assert!(is_signal_via_u8s(new::u8s::submit_first::i128()) == true);
+    }
+    #[cfg(feature = "hpe")]
+    {
         assert!(is_signal_via_len(new::len::signal_first::u64()) == true);
         assert!(is_signal_via_len(new::len::signal_first::i64()) == true);
         assert!(is_signal_via_len(new::len::signal_first::u128()) == true);
@@ -859,4 +2312,159 @@ const _CHECKS: () = {
         assert!(is_hash_via_u128(new::str::submit_first::u128()) == true);
         assert!(is_hash_via_i128(new::str::submit_first::i128()) == true);
     }
+    // ----
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    {
+        assert!(hash_via_bytes(new::u8s::signal_first::u64()) == 8);
+        assert!(hash_via_bytes(new::u8s::signal_first::i64()) == 8);
+        assert!(hash_via_bytes(new::u8s::signal_first::u128()) == 16);
+        assert!(hash_via_bytes(new::u8s::signal_first::i128()) == 16);
+
+        assert!(hash_via_bytes(new::u8s::submit_first::u64()) == 8);
+        assert!(hash_via_bytes(new::u8s::submit_first::i64()) == 8);
+        assert!(hash_via_bytes(new::u8s::submit_first::u128()) == 16);
+        assert!(hash_via_bytes(new::u8s::submit_first::i128()) == 16);
+    }
+    #[cfg(feature = "hpe")]
+    {
+        assert!(hash_via_bytes(new::len::signal_first::u64()) == 8);
+        assert!(hash_via_bytes(new::len::signal_first::i64()) == 8);
+        assert!(hash_via_bytes(new::len::signal_first::u128()) == 16);
+        assert!(hash_via_bytes(new::len::signal_first::i128()) == 16);
+
+        assert!(hash_via_bytes(new::len::submit_first::u64()) == 8);
+        assert!(hash_via_bytes(new::len::submit_first::i64()) == 8);
+        assert!(hash_via_bytes(new::len::submit_first::u128()) == 16);
+        assert!(hash_via_bytes(new::len::submit_first::i128()) == 16);
+    }
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    {
+        assert!(hash_via_bytes(new::str::signal_first::u64()) == 8);
+        assert!(hash_via_bytes(new::str::signal_first::i64()) == 8);
+        assert!(hash_via_bytes(new::str::signal_first::u128()) == 16);
+        assert!(hash_via_bytes(new::str::signal_first::i128()) == 16);
+
+        assert!(hash_via_bytes(new::str::submit_first::u64()) == 8);
+        assert!(hash_via_bytes(new::str::submit_first::i64()) == 8);
+        assert!(hash_via_bytes(new::str::submit_first::u128()) == 16);
+        assert!(hash_via_bytes(new::str::submit_first::i128()) == 16);
+    }
+
+    assert!(matches!(
+        try_signal_via(new::u8_stream::signal_first::i64()),
+        Some(SignalVia::U8Stream)
+    ));
+    assert!(matches!(
+        try_hash_via(new::u8_stream::signal_first::i64()),
+        Some(HashVia::I64)
+    ));
+    assert!(matches!(
+        try_signal_via(new::usize::submit_first::bytes()),
+        Some(SignalVia::Usize)
+    ));
+    assert!(matches!(
+        try_hash_via(new::usize::submit_first::bytes()),
+        Some(HashVia::Bytes)
+    ));
+    assert!(matches!(
+        try_signal_via(new::trusted::u128()),
+        Some(SignalVia::Trusted)
+    ));
+    #[cfg(not(feature = "flags"))]
+    {
+        // A submit-first len_carrier, hash bits out of range, and a signal-first trusted.
+        assert!(try_signal_via(0b000_0111).is_none());
+        assert!(try_hash_via(0b000_0111).is_none());
+        assert!(try_signal_via(0b101_0000).is_none());
+        assert!(try_hash_via(0b101_0000).is_none());
+        assert!(try_signal_via(0b000_1011).is_none());
+        assert!(try_hash_via(u8::MAX).is_none());
+    }
 };
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Debug;
+    use core::hash::Hash;
+
+    fn assert_hash_and_debug<T: Hash + Debug>() {}
+
+    #[test]
+    fn protocol_flags_are_hash_and_debug() {
+        assert_hash_and_debug::<ProtocolFlags>();
+    }
+
+    #[cfg(feature = "hpe")]
+    #[test]
+    fn protocol_flags_in_a_set() {
+        let mut set = std::collections::HashSet::new();
+        assert!(set.insert(new::len::signal_first::u64()));
+        assert!(set.insert(new::len::submit_first::u64()));
+        assert!(!set.insert(new::len::signal_first::u64()));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn try_via_all_protocols() {
+        for &flags in ALL_PROTOCOLS {
+            assert_eq!(try_signal_via(flags), Some(signal_via(flags)));
+            assert_eq!(try_hash_via(flags), Some(hash_via(flags)));
+        }
+    }
+
+    #[test]
+    fn all_protocols() {
+        for (i, &flags) in ALL_PROTOCOLS.iter().enumerate() {
+            assert!(is_valid(flags));
+            assert_eq!(flags_from_bits(flags_to_bits(flags)), Some(flags));
+            assert!(!ALL_PROTOCOLS[..i].contains(&flags));
+        }
+        // u8_stream, usize and trusted are always available.
+        let mut expected = 10 + 10 + 5;
+        if cfg!(feature = "hpe") {
+            expected += 21; // len, len_cookie, len_carrier
+        }
+        if cfg!(any(feature = "mx", feature = "ndd")) {
+            expected += 10; // u8s
+            if cfg!(feature = "hpe") {
+                expected += 10; // str
+            }
+        }
+        assert_eq!(ALL_PROTOCOLS.len(), expected);
+    }
+
+    #[test]
+    fn flags_bits_roundtrip() {
+        let mut valid = 0;
+        for bits in 0..=u8::MAX {
+            if let Some(flags) = flags_from_bits(bits) {
+                assert_eq!(flags_to_bits(flags), bits);
+                valid += 1;
+            }
+        }
+        // 6 signalling variants x 2 flows x 5 hash variants + 5 trusted + 1 len_carrier.
+        assert_eq!(valid, 66);
+    }
+
+    #[test]
+    fn invalid_bits_display() {
+        use std::string::ToString;
+        assert_eq!(
+            InvalidFlagsBits(0b1000_0000).to_string(),
+            "0b10000000 is not a valid encoding of ProtocolFlags."
+        );
+    }
+
+    #[cfg(feature = "flags")]
+    #[test]
+    fn try_from_u8() {
+        for &flags in ALL_PROTOCOLS {
+            let bits = u8::from(flags);
+            assert_eq!(ProtocolFlags::try_from(bits), Ok(flags));
+        }
+        for bits in [0b000_1011, 0b101_0000, 0b1000_0000, u8::MAX] {
+            assert_eq!(ProtocolFlags::try_from(bits), Err(InvalidFlagsBits(bits)));
+        }
+    }
+}
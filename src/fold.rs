@@ -0,0 +1,67 @@
+/// How [fold128] collapses a 128-bit hash to the `u64` that [crate::inject] takes.
+///
+/// Protocols that submit the hash via `u128`/`i128` still carry only a `u64` hash. So, if you have
+/// a 128-bit hash, choose deliberately how to fold it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Fold128 {
+    /// Keep the low 64 bits (the same as an `as u64` cast).
+    Low64,
+    /// XOR of the high and low 64 bits.
+    XorHalves,
+    /// XOR of the high and low 64 bits, multiplied (wrapping) by 2^64 divided by the golden ratio
+    /// (Fibonacci hashing). That spreads the entropy to the high bits, too.
+    FibonacciMix,
+}
+
+/// Fold a 128-bit `hash` to `u64`, per `strategy`. Deterministic.
+#[must_use]
+pub const fn fold128(hash: u128, strategy: Fold128) -> u64 {
+    let low = hash as u64;
+    let high = (hash >> 64) as u64;
+    match strategy {
+        Fold128::Low64 => low,
+        Fold128::XorHalves => high ^ low,
+        Fold128::FibonacciMix => (high ^ low).wrapping_mul(0x9E37_79B9_7F4A_7C15),
+    }
+}
+
+const _CHECKS: () = {
+    let hash = 0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210;
+    assert!(fold128(hash, Fold128::Low64) == 0xFEDC_BA98_7654_3210);
+    assert!(fold128(hash, Fold128::XorHalves) == 0xFFFF_FFFF_FFFF_FFFF);
+    assert!(fold128(hash, Fold128::FibonacciMix) == 0x61C8_8646_80B5_83EB);
+
+    let hash = 0x0000_0000_DEAD_BEEF_0000_0000_CAFE_BABE;
+    assert!(fold128(hash, Fold128::Low64) == 0xCAFE_BABE);
+    assert!(fold128(hash, Fold128::XorHalves) == 0x1453_0451);
+    assert!(fold128(hash, Fold128::FibonacciMix) == 0xE28A_8086_4F50_96A5);
+
+    assert!(fold128(0, Fold128::Low64) == 0);
+    assert!(fold128(0, Fold128::XorHalves) == 0);
+    assert!(fold128(0, Fold128::FibonacciMix) == 0);
+    assert!(fold128(1, Fold128::Low64) == 1);
+    assert!(fold128(1, Fold128::XorHalves) == 1);
+    assert!(fold128(1, Fold128::FibonacciMix) == 0x9E37_79B9_7F4A_7C15);
+    assert!(fold128(1 << 64, Fold128::Low64) == 0);
+    assert!(fold128(1 << 64, Fold128::XorHalves) == 1);
+    assert!(fold128(1 << 64, Fold128::FibonacciMix) == 0x9E37_79B9_7F4A_7C15);
+    assert!(fold128(u128::MAX, Fold128::Low64) == u64::MAX);
+    assert!(fold128(u128::MAX, Fold128::XorHalves) == 0);
+    assert!(fold128(u128::MAX, Fold128::FibonacciMix) == 0);
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fold128_strategies_differ() {
+        let hash = 0x0123_4567_89AB_CDEF_0000_0000_0000_0001;
+        assert_eq!(fold128(hash, Fold128::Low64), 1);
+        assert_eq!(fold128(hash, Fold128::XorHalves), 0x0123_4567_89AB_CDEE);
+        assert_ne!(
+            fold128(hash, Fold128::FibonacciMix),
+            fold128(hash, Fold128::XorHalves)
+        );
+    }
+}
@@ -1,29 +1,90 @@
+use core::fmt::{self, Display, Formatter};
 use core::hash::{BuildHasher, Hasher};
 
-use crate::flags::{self, Flow, ProtocolFlags, SignalVia};
-#[cfg(any(feature = "mx", feature = "ndd"))]
+use crate::flags::{self, Flow, HashVia, ProtocolFlags, SignalVia};
 use crate::signal;
 
+use crate::signal::USIZE_SIGNAL_HASH;
 #[cfg(feature = "hpe")]
-use crate::signal::LEN_SIGNAL_HASH;
+use crate::signal::len_signal_hash;
 #[cfg(all(feature = "hpe", feature = "chk-flow"))]
 use crate::signal::{LEN_SIGNAL_CHECK_FLOW_IS_SIGNAL_FIRST, LEN_SIGNAL_CHECK_FLOW_IS_SUBMIT_FIRST};
 use crate::state::SignalState;
+#[cfg(feature = "std")]
+use std::hash::{BuildHasherDefault, DefaultHasher, RandomState};
 
+/// A [Hasher] that wraps another [Hasher] `H`. It returns an injected hash (see [crate::inject]) if
+/// it received one, as per protocol `PF`.
+///
+/// Graceful passthrough: Keys that do NOT inject (and hence don't signal) are hashed by the wrapped
+/// [Hasher], and [Hasher::finish] returns exactly what the wrapped [Hasher] would return for them.
+/// So one map can contain both injecting and ordinary keys. This is a supported mode (and it is
+/// covered by tests). The exception are `trusted` protocols (see [crate::new::trusted]), which
+/// don't signal at all.
+///
+/// Graceful passthrough covers keys whose first write looks like a hash submitted by a submit-first
+/// protocol, too: it is passed on to the wrapped [Hasher] right away, so if ordinary data follows
+/// (rather than the signal), the result is still that of the wrapped [Hasher].
+///
+/// [Send] and [Sync] are auto-derived: they hold exactly when they hold for `H`. No pointer is
+/// stored - signals of `u8s` and `str` protocols are recognized by comparing addresses, and the
+/// static they point to is never written to. So hashers may move (or be shared) across threads.
 pub struct SignalledInjectionHasher<H: Hasher, const PF: ProtocolFlags> {
     hasher: H,
     state: SignalState,
+    /// Whether a signal was seen (whether it was accepted, or not).
+    #[cfg(feature = "chk-mixing")]
+    signalled: bool,
+    /// For `chk-width`: How the first write was done, if it was (possibly) a hash, but submitted
+    /// with a different `Hasher::write_xxx` than `PF` expects.
+    #[cfg(feature = "chk-width")]
+    mismatched_width: Option<HashVia>,
+    /// For `chk-signal-method`: Whether the most recent write was the first half of a signal of
+    /// `len_cookie` or `usize` protocols, but written with a different `Hasher::write_xxx` than
+    /// `PF` expects. Then the cookie must not follow.
+    #[cfg(feature = "chk-signal-method")]
+    wrong_first_half: bool,
 }
+
+// Compile-time check of the auto traits (see above). It's never called, but it's type-checked.
+fn _auto_traits<
+    H: Hasher + Send + Sync,
+    B: BuildHasher<Hasher = H> + Send + Sync,
+    const PF: ProtocolFlags,
+>() {
+    fn is_send_sync<T: Send + Sync>() {}
+    is_send_sync::<SignalledInjectionHasher<H, PF>>();
+    is_send_sync::<SignalledInjectionBuildHasher<H, B, PF>>();
+}
+
+/// Misuse of the signalling protocol, as reported by [SignalledInjectionHasher::finish_checked].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum FinishError {
+    /// A signal-first protocol signalled that a hash was coming, but no hash was submitted.
+    SignalledButNotSubmitted,
+}
+impl Display for FinishError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SignalledButNotSubmitted => {
+                f.write_str("The hash was signalled, but it was not submitted.")
+            }
+        }
+    }
+}
+impl core::error::Error for FinishError {}
+
 struct PossiblySubmitResult {
     must_write_data_afterwards: bool,
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, not(feature = "no-drop-guard")))]
     consumed: bool,
 }
 impl PossiblySubmitResult {
     const fn new(must_write_data_afterwards: bool) -> Self {
         Self {
             must_write_data_afterwards,
-            #[cfg(debug_assertions)]
+            #[cfg(all(debug_assertions, not(feature = "no-drop-guard")))]
             consumed: false,
         }
     }
@@ -35,15 +96,21 @@ impl PossiblySubmitResult {
     ///   already been set/modified by [SignalledInjectionHasher::possibly_submit].
     #[must_use]
     #[inline(always)]
+    // `mut` is needed only for the drop guard.
+    #[cfg_attr(
+        not(all(debug_assertions, not(feature = "no-drop-guard"))),
+        allow(unused_mut)
+    )]
     fn must_write_data_afterwards(mut self) -> bool {
-        #[cfg(debug_assertions)]
+        #[cfg(all(debug_assertions, not(feature = "no-drop-guard")))]
         {
             self.consumed = true;
         }
         self.must_write_data_afterwards
     }
 }
-#[cfg(debug_assertions)]
+/// Not with `no-drop-guard` cargo feature.
+#[cfg(all(debug_assertions, not(feature = "no-drop-guard")))]
 impl Drop for PossiblySubmitResult {
     fn drop(&mut self) {
         debug_assert!(self.consumed);
@@ -52,16 +119,378 @@ impl Drop for PossiblySubmitResult {
 impl<H: Hasher, const PF: ProtocolFlags> SignalledInjectionHasher<H, PF> {
     #[inline]
     const fn new(hasher: H) -> Self {
+        #[cfg(feature = "chk")]
+        const {
+            flags::assert_usable(PF)
+        };
         Self {
             hasher,
             state: SignalState::new_nothing_written(),
+            #[cfg(feature = "chk-mixing")]
+            signalled: false,
+            #[cfg(feature = "chk-width")]
+            mismatched_width: None,
+            #[cfg(feature = "chk-signal-method")]
+            wrong_first_half: false,
+        }
+    }
+    /// Inject `hash` (per this hasher's protocol `PF`) and return the result of
+    /// [Hasher::finish].
+    ///
+    /// The state is reset both before and after, so the same instance can be reused for a stream
+    /// of hashes, rather than building a new [Hasher] for each of them. (The underlying [Hasher] is
+    /// NOT reset, but its result is not used for injected hashes anyway.)
+    #[must_use = "this returns the injected hash (the state is reset afterwards)"]
+    pub fn inject_and_finish(&mut self, hash: u64) -> u64 {
+        self.reset_state();
+        crate::inject::<_, PF>(self, hash);
+        let result = self.finish();
+        self.reset_state();
+        result
+    }
+    /// Inject `hash` into this hasher, as per its protocol `PF` - the same as [crate::inject], but
+    /// as a method. Then [Hasher::finish] returns `hash`.
+    ///
+    /// ```
+    /// use core::hash::Hasher;
+    /// use hash_injector::{ProtocolFlags, SignalledInjectionHasher, new};
+    /// use std::hash::DefaultHasher;
+    ///
+    /// const PF: ProtocolFlags = new::u8_stream::submit_first::u64();
+    /// let mut hasher = SignalledInjectionHasher::<DefaultHasher, PF>::default();
+    /// hasher.write_injected(42);
+    /// assert_eq!(hasher.finish(), 42);
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn write_injected(&mut self, hash: u64) {
+        crate::inject::<_, PF>(self, hash);
+    }
+    #[inline(always)]
+    pub(crate) fn reset_state(&mut self) {
+        self.state = SignalState::new_nothing_written();
+        #[cfg(feature = "chk-mixing")]
+        {
+            self.signalled = false;
+        }
+        #[cfg(feature = "chk-width")]
+        {
+            self.mismatched_width = None;
+        }
+        #[cfg(feature = "chk-signal-method")]
+        {
+            self.wrong_first_half = false;
+        }
+    }
+    /// Whether an injected hash was received (so [Hasher::finish] returns it).
+    #[inline(always)]
+    pub(crate) const fn is_hash_received(&self) -> bool {
+        self.state.is_hash_received()
+    }
+    /// Like [Hasher::finish], but instead of returning a hash for a state that is invalid for the
+    /// protocol `PF`, return [FinishError].
+    ///
+    /// Unlike checks enabled with `chk` and related cargo features, this is always available and
+    /// cheap, so it can serve as a lightweight correctness net in production code.
+    pub fn finish_checked(&self) -> Result<u64, FinishError> {
+        match flags::flow(PF) {
+            Flow::SignalFirst if self.state.is_signalled_proposal_coming(PF) => {
+                Err(FinishError::SignalledButNotSubmitted)
+            }
+            _ => Ok(self.finish()),
         }
     }
+    /// Like [Hasher::finish], but consume the hasher. Handy where the hasher would otherwise have
+    /// to outlive the expression (for example, at the end of a builder chain).
+    ///
+    /// ```
+    /// use core::hash::{BuildHasher, Hash};
+    /// use hash_injector::{ProtocolFlags, SignalledInjectionBuildHasher, inject, new};
+    ///
+    /// const PF: ProtocolFlags = new::u8_stream::signal_first::u64();
+    /// let build = SignalledInjectionBuildHasher::<_, _, PF>::new(std::hash::RandomState::new());
+    ///
+    /// let mut h = build.build_hasher();
+    /// inject::<_, PF>(&mut h, 42);
+    /// let v = h.finish_into();
+    /// assert_eq!(v, 42);
+    ///
+    /// let mut h = build.build_hasher();
+    /// "ordinary".hash(&mut h);
+    /// assert_eq!(h.finish_into(), build.hash_one("ordinary"));
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn finish_into(self) -> u64 {
+        self.finish()
+    }
+    /// Record that a signal was seen, for `chk-mixing`. With `chk-width` (in debug mode only), for
+    /// submit-first protocols, assert that the hash was not submitted with a mismatched width.
+    /// No-op otherwise.
+    #[inline(always)]
+    #[track_caller]
+    fn signal_seen(&mut self) {
+        #[cfg(feature = "chk-mixing")]
+        {
+            self.signalled = true;
+        }
+        #[cfg(feature = "chk-width")]
+        if let Some(written) = self.mismatched_width {
+            debug_assert!(
+                false,
+                "The hash was submitted via {:?}, but the protocol submits it via {:?}.",
+                written,
+                flags::hash_via(PF)
+            );
+        }
+    }
+    /// With `chk-width` (in debug mode only): Given that `written` is NOT how protocol `PF`
+    /// submits the hash, assert that this write is not where the hash is expected. For
+    /// submit-first protocols that is known only once the signal comes (see
+    /// [Self::signal_seen]). No-op otherwise.
+    ///
+    /// Call this before the state changes.
+    #[inline(always)]
+    #[track_caller]
+    fn check_width(&mut self, _written: HashVia) {
+        #[cfg(feature = "chk-width")]
+        {
+            let hash_expected = if flags::is_signal_via_trusted(PF) {
+                self.state.is_nothing_written()
+            } else {
+                match flags::flow(PF) {
+                    // The `len_carrier` protocol carries the hash in lengths only.
+                    Flow::SignalFirst => {
+                        !flags::is_signal_via_len_carrier(PF)
+                            && self.state.is_signalled_proposal_coming(PF)
+                    }
+                    Flow::SubmitFirst => {
+                        if self.state.is_nothing_written() {
+                            self.mismatched_width = Some(_written);
+                        }
+                        false
+                    }
+                }
+            };
+            debug_assert!(
+                !hash_expected,
+                "The hash was submitted via {:?}, but the protocol submits it via {:?}.",
+                _written,
+                flags::hash_via(PF)
+            );
+        }
+    }
+    /// With `chk-signal-method`: Panic, since a signal was written with `written_with`, but `PF`
+    /// signals with a different `Hasher::write_xxx`.
+    #[cfg(feature = "chk-signal-method")]
+    #[track_caller]
+    fn wrong_signal_method(&self, written_with: &str) -> ! {
+        let expected = match flags::signal_via(PF) {
+            SignalVia::U8s => "Hasher::write",
+            SignalVia::Len => "Hasher::write_length_prefix",
+            SignalVia::Str => "Hasher::write_str",
+            SignalVia::Trusted => "nothing (it doesn't signal)",
+            SignalVia::U8Stream => "Hasher::write_u8",
+            SignalVia::LenCookie => "Hasher::write_length_prefix(0), then Hasher::write_u64",
+            SignalVia::Usize => "Hasher::write_usize, then Hasher::write_u64",
+            SignalVia::LenCarrier => "Hasher::write_length_prefix",
+        };
+        panic!(
+            "A signal was written with {written_with}, but protocol {:?} signals with {expected}.",
+            flags::signal_via(PF)
+        );
+    }
+    /// With `chk-mixing` (in debug mode only), assert that either a complete signal (signal and
+    /// hash) was received, or no signal at all. No-op otherwise.
+    #[inline(always)]
+    #[track_caller]
+    fn assert_not_mixed(&self) {
+        #[cfg(feature = "chk-mixing")]
+        debug_assert!(
+            self.signalled == self.state.is_hash_received(),
+            "Partial signalling (or ordinary data mixed with signalling) within one Hasher: {:?}.",
+            self.state
+        );
+    }
     // @TODO if this doesn't optimize away in release, replace with a macro.
     #[inline(always)]
+    #[track_caller]
     fn written_ordinary_hash(&mut self) {
+        if flags::is_signal_via_u8_stream(PF) {
+            // Any bytes of a partial signal were ordinary data, too.
+            self.state.u8_stream_matched = 0;
+        }
+        if flags::is_signal_via_len_cookie(PF) || flags::is_signal_via_usize(PF) {
+            // So was the first half of a signal written just before.
+            self.state.cookie_pending = false;
+        }
+        #[cfg(feature = "hpe")]
+        if flags::is_signal_via_len_carrier(PF) {
+            // So was the high half of a hash carried just before.
+            self.state.len_carrier_high = None;
+        }
+        #[cfg(feature = "chk-signal-method")]
+        {
+            self.wrong_first_half = false;
+        }
         self.state.set_written_ordinary_hash();
     }
+    /// For `u8_stream` protocols: If a partial signal was written, those bytes are ordinary data,
+    /// after all. No-op otherwise.
+    #[inline(always)]
+    #[track_caller]
+    fn u8_stream_interrupted(&mut self) {
+        if flags::is_signal_via_u8_stream(PF) && self.state.u8_stream_matched > 0 {
+            self.state
+                .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
+            self.written_ordinary_hash();
+        }
+    }
+    /// For `len_cookie` and `usize` protocols: If the first half of a signal was written (and the
+    /// cookie did not follow), it is ordinary data, after all. No-op otherwise.
+    #[inline(always)]
+    #[track_caller]
+    fn cookie_interrupted(&mut self) {
+        if (flags::is_signal_via_len_cookie(PF) || flags::is_signal_via_usize(PF))
+            && self.state.cookie_pending
+        {
+            self.state
+                .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
+            self.written_ordinary_hash();
+        }
+    }
+    /// For `len_cookie` and `usize` protocols: The cookie was written right after the first half of
+    /// a signal, so the pair is a signal.
+    #[track_caller]
+    fn cookie_signalled(&mut self) {
+        self.state.cookie_pending = false;
+        self.signal_seen();
+        match flags::flow(PF) {
+            Flow::SubmitFirst => {
+                // A second signal is a bug: set_hash_received() panics in debug mode.
+                if self.state.is_hash_possibly_submitted(PF) || self.state.is_hash_received() {
+                    self.state.set_hash_received();
+                } else {
+                    #[cfg(feature = "chk")]
+                    assert!(
+                        false,
+                        "Expected state HashPossiblySubmitted, but it was {:?}.",
+                        self.state
+                    );
+                    self.hasher.write_u64(signal::cookie(PF));
+                    self.written_ordinary_hash();
+                }
+            }
+            Flow::SignalFirst => {
+                self.signalled_first();
+            }
+        }
+    }
+    /// For signal-first protocols: The signal came. Ordinary data may be written before it only by
+    /// [crate::PartialInject], which signals twice in a row. Then the hash that follows is combined
+    /// with that data. Whether ordinary data came before a single signal is checked once the hash
+    /// comes.
+    #[inline(always)]
+    #[track_caller]
+    fn signalled_first(&mut self) {
+        if self.state.is_signalled_proposal_coming(PF) {
+            // The second signal (of `signal::inject_combined`).
+            self.state.combine_with_ordinary = self.state.signalled_after_ordinary;
+            return;
+        }
+        self.state.assert_nothing_written_or_ordinary_hash();
+        self.state.signalled_after_ordinary = self.state.is_written_ordinary_hash();
+        self.state.set_signalled_proposal_coming(PF);
+    }
+    /// For signal-first protocols: The hash `i` came after the signal.
+    #[inline(always)]
+    #[track_caller]
+    fn received_signalled_first(&mut self, i: u64) {
+        let hash = if self.state.combine_with_ordinary {
+            signal::tag_hash(i, self.hasher.finish())
+        } else {
+            // For example, a raw `Option<T>` writes its discriminant before `T` injects.
+            #[cfg(feature = "chk")]
+            assert!(
+                !self.state.signalled_after_ordinary,
+                "Ordinary data was written before the signal. Only PartialInject may combine them."
+            );
+            i
+        };
+        self.state = SignalState::new_hash_received(hash);
+    }
+    /// [Hasher::write] of bytes that are not a signal. For protocols that submit the hash as bytes
+    /// (see [crate::new::u8_stream::signal_first::bytes] and alike), 8 bytes may be the hash.
+    #[inline(always)]
+    #[track_caller]
+    fn write_data(&mut self, bytes: &[u8]) {
+        if let Ok(hash) = <[u8; 8]>::try_from(bytes) {
+            if flags::is_hash_via_bytes(PF) {
+                if self
+                    .possibly_submit(u64::from_le_bytes(hash))
+                    .must_write_data_afterwards()
+                {
+                    self.hasher.write(bytes);
+                }
+                return;
+            }
+            self.check_width(HashVia::Bytes);
+        }
+        match flags::flow(PF) {
+            Flow::SubmitFirst => self
+                .state
+                .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF),
+            Flow::SignalFirst => self.state.assert_nothing_written_or_ordinary_hash(),
+        }
+        self.hasher.write(bytes);
+        self.written_ordinary_hash();
+    }
+    /// [Hasher::write_u8] for `u8_stream` protocols.
+    #[track_caller]
+    fn write_u8_stream(&mut self, i: u8) {
+        // Always pass the byte on. If it turns out to be a part of a signal, the underlying
+        // Hasher's result is not used anyway.
+        self.hasher.write_u8(i);
+        let matched = self.state.u8_stream_matched as usize;
+        if i == signal::U8_STREAM_SIGNAL_HASH[matched] {
+            if matched + 1 < signal::U8_STREAM_SIGNAL_HASH.len() {
+                self.state.u8_stream_matched += 1;
+                return;
+            }
+            self.state.u8_stream_matched = 0;
+            self.signal_seen();
+            match flags::flow(PF) {
+                Flow::SubmitFirst => {
+                    // A second signal is a bug: set_hash_received() panics in debug mode.
+                    if self.state.is_hash_possibly_submitted(PF) || self.state.is_hash_received() {
+                        self.state.set_hash_received();
+                    } else {
+                        #[cfg(feature = "chk")]
+                        assert!(
+                            false,
+                            "Expected state HashPossiblySubmitted, but it was {:?}.",
+                            self.state
+                        );
+                        self.written_ordinary_hash();
+                    }
+                }
+                Flow::SignalFirst => {
+                    self.signalled_first();
+                }
+            }
+        } else {
+            // Any previously matched bytes were ordinary data. This byte may start a new signal
+            // (since all bytes of the signal are distinct, it can't continue an earlier one).
+            self.u8_stream_interrupted();
+            if i == signal::U8_STREAM_SIGNAL_HASH[0] {
+                self.state.u8_stream_matched = 1;
+            } else {
+                self.state
+                    .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
+                self.written_ordinary_hash();
+            }
+        }
+    }
     /// Submit, or possibly submit, hash `i`, as appropriate per the state and the flow.
     ///
     /// The caller MUSt use the result and depending on its
@@ -72,11 +501,35 @@ impl<H: Hasher, const PF: ProtocolFlags> SignalledInjectionHasher<H, PF> {
     /// We do not use a function pointer to call back to write the given data, because the caller's
     /// actual data may also be a `i64, u128, i128`.
     #[must_use]
+    #[inline]
+    #[track_caller]
     fn possibly_submit(&mut self, i: u64) -> PossiblySubmitResult {
+        self.u8_stream_interrupted();
+        self.cookie_interrupted();
+        #[cfg(feature = "chk-signal-method")]
+        {
+            self.wrong_first_half = false;
+        }
+        if flags::is_signal_via_trusted(PF) {
+            // No signal: The first write of the hash IS the injected hash. Any more writes mean
+            // that the protocol is misused (mixed with ordinary hashing).
+            self.state.assert_nothing_written();
+            return if self.state.is_nothing_written() {
+                self.signal_seen();
+                self.state = SignalState::new_hash_received(i);
+                PossiblySubmitResult::new(false)
+            } else {
+                self.written_ordinary_hash();
+                PossiblySubmitResult::new(true)
+            };
+        }
         match flags::flow(PF) {
             Flow::SignalFirst => {
-                if self.state.is_signalled_proposal_coming(PF) {
-                    self.state = SignalState::new_hash_received(i);
+                // The `len_carrier` protocol carries the hash in lengths only.
+                if self.state.is_signalled_proposal_coming(PF)
+                    && !flags::is_signal_via_len_carrier(PF)
+                {
+                    self.received_signalled_first(i);
                     PossiblySubmitResult::new(false)
                 } else {
                     self.state.assert_nothing_written_or_ordinary_hash();
@@ -106,9 +559,52 @@ impl<H: Hasher, const PF: ProtocolFlags> SignalledInjectionHasher<H, PF> {
         }
     }
 }
+/// This lets [SignalledInjectionHasher] itself be used with [core::hash::BuildHasherDefault] -
+/// without [SignalledInjectionBuildHasher], in simple cases.
+impl<H: Hasher + Default, const PF: ProtocolFlags> Default for SignalledInjectionHasher<H, PF> {
+    fn default() -> Self {
+        Self::new(H::default())
+    }
+}
+/// Wrap a [Hasher] (moving it).
+///
+/// ```
+/// use core::hash::Hasher;
+/// use hash_injector::{ProtocolFlags, SignalledInjectionHasher, inject, new};
+/// use std::hash::DefaultHasher;
+///
+/// const PF: ProtocolFlags = new::u8_stream::signal_first::u64();
+/// let mut hasher: SignalledInjectionHasher<_, PF> = DefaultHasher::new().into();
+/// inject::<_, PF>(&mut hasher, 42);
+/// assert_eq!(hasher.finish(), 42);
+/// ```
+impl<H: Hasher, const PF: ProtocolFlags> From<H> for SignalledInjectionHasher<H, PF> {
+    fn from(hasher: H) -> Self {
+        Self::new(hasher)
+    }
+}
+impl<'h, H: Hasher, const PF: ProtocolFlags> SignalledInjectionHasher<&'h mut H, PF> {
+    /// Wrap a borrowed (for example, long-lived) [Hasher], rather than moving it. Ordinary data is
+    /// written to it, as usual. After the wrapper is dropped, the borrowed [Hasher] can be used
+    /// again.
+    ///
+    /// This needs no extra machinery, since `&mut H` is a [Hasher] (for any `H: Hasher`).
+    pub fn borrow(hasher: &'h mut H) -> Self {
+        Self::new(hasher)
+    }
+}
 impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H, PF> {
     #[inline]
+    #[track_caller]
     fn finish(&self) -> u64 {
+        self.assert_not_mixed();
+        #[cfg(feature = "chk-empty")]
+        assert!(
+            !self.state.is_nothing_written()
+                || self.state.u8_stream_matched > 0
+                || self.state.cookie_pending,
+            "finish() called, but nothing was written (no hash was injected)."
+        );
         if self.state.is_hash_received() {
             self.state.hash
         } else {
@@ -120,20 +616,39 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
     /// This does NOT signal, even if you handed it the same bytes as [`inject_via_len`] passes
     /// through `write_length_prefix` and `write_u64` when signalling.
     #[inline]
+    #[track_caller]
     fn write(&mut self, bytes: &[u8]) {
         match flags::signal_via(PF) {
-            SignalVia::Len | SignalVia::Str => {
-                self.state
-                    .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
-                self.hasher.write(bytes);
-                self.written_ordinary_hash();
+            SignalVia::Len
+            | SignalVia::Str
+            | SignalVia::Trusted
+            | SignalVia::U8Stream
+            | SignalVia::LenCookie
+            | SignalVia::Usize
+            | SignalVia::LenCarrier => {
+                #[cfg(feature = "chk-signal-method")]
+                {
+                    #[cfg(any(feature = "mx", feature = "ndd"))]
+                    if signal::is_ptr_signal_hash(bytes.as_ptr(), bytes.len()) {
+                        self.wrong_signal_method("Hasher::write");
+                    }
+                    if flags::is_signal_via_u8_stream(PF) && bytes == signal::U8_STREAM_SIGNAL_HASH
+                    {
+                        self.wrong_signal_method("Hasher::write");
+                    }
+                }
+                self.write_data(bytes);
             }
             SignalVia::U8s => {
                 match flags::flow(PF) {
                     Flow::SubmitFirst => {
                         #[cfg(any(feature = "mx", feature = "ndd"))]
-                        if signal::is_ptr_signal_hash(bytes.as_ptr()) {
-                            if self.state.is_hash_possibly_submitted(PF) {
+                        if signal::is_ptr_signal_hash(bytes.as_ptr(), bytes.len()) {
+                            self.signal_seen();
+                            // A second signal is a bug: set_hash_received() panics in debug mode.
+                            if self.state.is_hash_possibly_submitted(PF)
+                                || self.state.is_hash_received()
+                            {
                                 self.state.set_hash_received();
                             } else {
                                 #[cfg(feature = "chk")]
@@ -149,55 +664,50 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
                         } else {
                             #[cfg(feature = "chk-flow")]
                             {
-                                if signal::is_ptr_signal_check_flow_is_submit_first(bytes.as_ptr())
-                                {
+                                if signal::is_ptr_signal_check_flow_is_submit_first(
+                                    bytes.as_ptr(),
+                                    bytes.len(),
+                                ) {
                                     return; // just being checked (no data to write)
                                 }
                                 assert!(!signal::is_ptr_signal_check_flow_is_signal_first(
-                                    bytes.as_ptr()
+                                    bytes.as_ptr(),
+                                    bytes.len()
                                 ));
                             }
 
-                            self.state
-                                .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
-                            self.hasher.write(bytes);
-                            self.written_ordinary_hash();
+                            self.write_data(bytes);
                         }
                         #[cfg(not(any(feature = "mx", feature = "ndd")))]
                         {
-                            self.state
-                                .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
-                            self.hasher.write(bytes);
-                            self.written_ordinary_hash();
+                            self.write_data(bytes);
                         }
                     }
                     Flow::SignalFirst => {
                         #[cfg(any(feature = "mx", feature = "ndd"))]
-                        if signal::is_ptr_signal_hash(bytes.as_ptr()) {
-                            self.state.assert_nothing_written();
-                            self.state.set_signalled_proposal_coming(PF);
+                        if signal::is_ptr_signal_hash(bytes.as_ptr(), bytes.len()) {
+                            self.signal_seen();
+                            self.signalled_first();
                         } else {
                             #[cfg(feature = "chk-flow")]
                             {
-                                if signal::is_ptr_signal_check_flow_is_signal_first(bytes.as_ptr())
-                                {
+                                if signal::is_ptr_signal_check_flow_is_signal_first(
+                                    bytes.as_ptr(),
+                                    bytes.len(),
+                                ) {
                                     return; // just being checked (no data to write)
                                 }
                                 assert!(!signal::is_ptr_signal_check_flow_is_submit_first(
-                                    bytes.as_ptr()
+                                    bytes.as_ptr(),
+                                    bytes.len()
                                 ));
                             }
 
-                            self.state.assert_nothing_written_or_ordinary_hash();
-                            self.hasher.write(bytes);
-                            self.written_ordinary_hash();
+                            self.write_data(bytes);
                         }
                         #[cfg(not(any(feature = "mx", feature = "ndd")))]
                         {
-                            self.state
-                                .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
-                            self.hasher.write(bytes);
-                            self.written_ordinary_hash();
+                            self.write_data(bytes);
                         }
                     }
                 }
@@ -206,13 +716,19 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
     }
 
     #[inline]
+    #[track_caller]
     fn write_u8(&mut self, i: u8) {
-        self.state
-            .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
-        self.hasher.write_u8(i);
-        self.written_ordinary_hash();
+        if flags::is_signal_via_u8_stream(PF) {
+            self.write_u8_stream(i);
+        } else {
+            self.state
+                .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
+            self.hasher.write_u8(i);
+            self.written_ordinary_hash();
+        }
     }
     #[inline]
+    #[track_caller]
     fn write_u16(&mut self, i: u16) {
         self.state
             .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
@@ -220,18 +736,33 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
         self.written_ordinary_hash();
     }
     #[inline]
+    #[track_caller]
     fn write_u32(&mut self, i: u32) {
         self.state
             .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
         self.hasher.write_u32(i);
         self.written_ordinary_hash();
     }
+    #[inline]
+    #[track_caller]
     fn write_u64(&mut self, i: u64) {
+        if (flags::is_signal_via_len_cookie(PF) || flags::is_signal_via_usize(PF))
+            && self.state.cookie_pending
+            && i == signal::cookie(PF)
+        {
+            self.cookie_signalled();
+            return;
+        }
+        #[cfg(feature = "chk-signal-method")]
+        if self.wrong_first_half && i == signal::cookie(PF) {
+            self.wrong_signal_method("a wrong Hasher::write_xxx for its first half");
+        }
         if flags::is_hash_via_u64(PF) {
             if self.possibly_submit(i).must_write_data_afterwards() {
                 self.hasher.write_u64(i);
             }
         } else {
+            self.check_width(HashVia::U64);
             self.state
                 .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
             self.hasher.write_u64(i);
@@ -239,12 +770,14 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
         }
     }
     #[inline]
+    #[track_caller]
     fn write_u128(&mut self, i: u128) {
         if flags::is_hash_via_u128(PF) {
             if self.possibly_submit(i as u64).must_write_data_afterwards() {
                 self.hasher.write_u128(i);
             }
         } else {
+            self.check_width(HashVia::U128);
             self.state
                 .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
             self.hasher.write_u128(i);
@@ -252,13 +785,46 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
         }
     }
     #[inline]
+    #[track_caller]
     fn write_usize(&mut self, i: usize) {
-        self.state
-            .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
-        self.hasher.write_usize(i);
-        self.written_ordinary_hash();
+        if flags::is_signal_via_usize(PF) {
+            // An earlier usize::MAX (if any) was not followed by the cookie.
+            self.cookie_interrupted();
+            // Right after a signal, this may start the second signal of `signal::inject_combined`.
+            if !(i == USIZE_SIGNAL_HASH
+                && flags::is_signal_first(PF)
+                && self.state.is_signalled_proposal_coming(PF))
+            {
+                self.state
+                    .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
+            }
+            // Always pass the value on. If it turns out to be a part of a signal, the underlying
+            // Hasher's result is not used anyway.
+            self.hasher.write_usize(i);
+            if i == USIZE_SIGNAL_HASH {
+                self.state.cookie_pending = true;
+            } else {
+                self.written_ordinary_hash();
+            }
+        } else {
+            #[cfg(all(feature = "chk-signal-method", feature = "hpe"))]
+            if (flags::is_signal_via_len(PF) || flags::is_signal_via_len_carrier(PF))
+                && i == len_signal_hash(PF)
+            {
+                self.wrong_signal_method("Hasher::write_usize");
+            }
+            self.state
+                .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
+            self.hasher.write_usize(i);
+            self.written_ordinary_hash();
+            #[cfg(feature = "chk-signal-method")]
+            {
+                self.wrong_first_half = flags::is_signal_via_len_cookie(PF) && i == 0;
+            }
+        }
     }
     #[inline]
+    #[track_caller]
     fn write_i8(&mut self, i: i8) {
         self.state
             .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
@@ -266,6 +832,7 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
         self.written_ordinary_hash();
     }
     #[inline]
+    #[track_caller]
     fn write_i16(&mut self, i: i16) {
         self.state
             .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
@@ -273,6 +840,7 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
         self.written_ordinary_hash();
     }
     #[inline]
+    #[track_caller]
     fn write_i32(&mut self, i: i32) {
         self.state
             .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
@@ -280,12 +848,14 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
         self.written_ordinary_hash();
     }
     #[inline]
+    #[track_caller]
     fn write_i64(&mut self, i: i64) {
         if flags::is_hash_via_i64(PF) {
             if self.possibly_submit(i as u64).must_write_data_afterwards() {
                 self.hasher.write_i64(i);
             }
         } else {
+            self.check_width(HashVia::I64);
             self.state
                 .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
             self.hasher.write_i64(i);
@@ -293,12 +863,14 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
         }
     }
     #[inline]
+    #[track_caller]
     fn write_i128(&mut self, i: i128) {
         if flags::is_hash_via_i128(PF) {
             if self.possibly_submit(i as u64).must_write_data_afterwards() {
                 self.hasher.write_i128(i);
             }
         } else {
+            self.check_width(HashVia::I128);
             self.state
                 .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
             self.hasher.write_i128(i);
@@ -306,27 +878,86 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
         }
     }
     #[inline]
+    #[track_caller]
     fn write_isize(&mut self, i: isize) {
         self.state
             .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
         self.hasher.write_isize(i);
         self.written_ordinary_hash();
     }
+    // Without `hpe` these two methods can't be implemented here (they are unstable). Their default
+    // implementations forward to `write_usize` and `write` (and `write_u8`) above, so ordinary
+    // lengths and strings still reach the underlying Hasher.
     #[cfg(feature = "hpe")]
+    #[inline]
+    #[track_caller]
     fn write_length_prefix(&mut self, len: usize) {
         // Logical branches/their conditions can get optimized away (const)
         match flags::signal_via(PF) {
-            SignalVia::U8s | SignalVia::Str => {
+            SignalVia::U8s
+            | SignalVia::Str
+            | SignalVia::Trusted
+            | SignalVia::U8Stream
+            | SignalVia::Usize => {
                 self.state
                     .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
                 self.hasher.write_length_prefix(len);
                 self.written_ordinary_hash();
+                #[cfg(feature = "chk-signal-method")]
+                {
+                    self.wrong_first_half =
+                        flags::is_signal_via_usize(PF) && len == USIZE_SIGNAL_HASH;
+                }
+            }
+            SignalVia::LenCarrier => {
+                if self.state.is_signalled_proposal_coming(PF) {
+                    // The hash, carried in two lengths: its high, and then its low 32 bits.
+                    match self.state.len_carrier_high.take() {
+                        None => self.state.len_carrier_high = Some(len as u32),
+                        Some(high) => {
+                            self.received_signalled_first(
+                                u64::from(high) << 32 | u64::from(len as u32),
+                            );
+                        }
+                    }
+                } else if len == len_signal_hash(PF) {
+                    self.signal_seen();
+                    self.signalled_first();
+                } else {
+                    self.state.assert_nothing_written_or_ordinary_hash();
+                    self.hasher.write_length_prefix(len);
+                    self.written_ordinary_hash();
+                }
+            }
+            SignalVia::LenCookie => {
+                // An earlier zero length (if any) was not followed by the cookie.
+                self.cookie_interrupted();
+                // See the same check in write_usize().
+                if !(len == 0
+                    && flags::is_signal_first(PF)
+                    && self.state.is_signalled_proposal_coming(PF))
+                {
+                    self.state
+                        .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
+                }
+                // Always pass the length on. If it turns out to be a part of a signal, the
+                // underlying Hasher's result is not used anyway.
+                self.hasher.write_length_prefix(len);
+                if len == 0 {
+                    self.state.cookie_pending = true;
+                } else {
+                    self.written_ordinary_hash();
+                }
             }
             SignalVia::Len => {
                 match flags::flow(PF) {
                     Flow::SubmitFirst => {
-                        if len == LEN_SIGNAL_HASH {
-                            if self.state.is_hash_possibly_submitted(PF) {
+                        if len == len_signal_hash(PF) {
+                            self.signal_seen();
+                            // A second signal is a bug: set_hash_received() panics in debug mode.
+                            if self.state.is_hash_possibly_submitted(PF)
+                                || self.state.is_hash_received()
+                            {
                                 self.state.set_hash_received();
                             } else {
                                 #[cfg(feature = "chk")]
@@ -340,12 +971,20 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
                                 self.written_ordinary_hash();
                             }
                         } else {
+                            // Only right after the hash was received this length is a check-flow
+                            // sentinel. Otherwise it is ordinary data (for example, the length of
+                            // a ZST slice).
                             #[cfg(feature = "chk-flow")]
                             {
-                                if len == LEN_SIGNAL_CHECK_FLOW_IS_SUBMIT_FIRST {
+                                if len == LEN_SIGNAL_CHECK_FLOW_IS_SUBMIT_FIRST
+                                    && self.state.is_hash_received()
+                                {
                                     return; // just being checked (no data to write)
                                 }
-                                assert_ne!(len, LEN_SIGNAL_CHECK_FLOW_IS_SIGNAL_FIRST);
+                                assert_ne!(
+                                    len, LEN_SIGNAL_CHECK_FLOW_IS_SIGNAL_FIRST,
+                                    "Hash signals first, but the protocol submits first."
+                                );
                             }
 
                             self.state
@@ -355,16 +994,22 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
                         }
                     }
                     Flow::SignalFirst => {
-                        if len == LEN_SIGNAL_HASH {
-                            self.state.assert_nothing_written();
-                            self.state.set_signalled_proposal_coming(PF);
+                        if len == len_signal_hash(PF) {
+                            self.signal_seen();
+                            self.signalled_first();
                         } else {
+                            // See the same check in Flow::SubmitFirst above.
                             #[cfg(feature = "chk-flow")]
                             {
-                                if len == LEN_SIGNAL_CHECK_FLOW_IS_SIGNAL_FIRST {
+                                if len == LEN_SIGNAL_CHECK_FLOW_IS_SIGNAL_FIRST
+                                    && self.state.is_hash_received()
+                                {
                                     return; // just being checked (no data to write)
                                 }
-                                assert_ne!(len, LEN_SIGNAL_CHECK_FLOW_IS_SUBMIT_FIRST);
+                                assert_ne!(
+                                    len, LEN_SIGNAL_CHECK_FLOW_IS_SUBMIT_FIRST,
+                                    "Hash submits first, but the protocol signals first."
+                                );
                             }
 
                             self.state.assert_nothing_written_or_ordinary_hash();
@@ -379,9 +1024,20 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
 
     #[cfg(feature = "hpe")]
     #[inline]
+    #[track_caller]
     fn write_str(&mut self, s: &str) {
         match flags::signal_via(PF) {
-            SignalVia::U8s | SignalVia::Len => {
+            SignalVia::U8s
+            | SignalVia::Len
+            | SignalVia::Trusted
+            | SignalVia::U8Stream
+            | SignalVia::LenCookie
+            | SignalVia::Usize
+            | SignalVia::LenCarrier => {
+                #[cfg(all(feature = "chk-signal-method", any(feature = "mx", feature = "ndd")))]
+                if signal::is_ptr_signal_hash(s.as_ptr(), s.len()) {
+                    self.wrong_signal_method("Hasher::write_str");
+                }
                 self.state
                     .assert_nothing_written_or_ordinary_hash_or_possibly_submitted(PF);
                 self.hasher.write_str(s);
@@ -391,8 +1047,12 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
                 match flags::flow(PF) {
                     Flow::SubmitFirst => {
                         #[cfg(any(feature = "mx", feature = "ndd"))]
-                        if signal::is_ptr_signal_hash(s.as_ptr()) {
-                            if self.state.is_hash_possibly_submitted(PF) {
+                        if signal::is_ptr_signal_hash(s.as_ptr(), s.len()) {
+                            self.signal_seen();
+                            // A second signal is a bug: set_hash_received() panics in debug mode.
+                            if self.state.is_hash_possibly_submitted(PF)
+                                || self.state.is_hash_received()
+                            {
                                 self.state.set_hash_received();
                             } else {
                                 #[cfg(feature = "chk")]
@@ -408,11 +1068,15 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
                         } else {
                             #[cfg(feature = "chk-flow")]
                             {
-                                if signal::is_ptr_signal_check_flow_is_submit_first(s.as_ptr()) {
+                                if signal::is_ptr_signal_check_flow_is_submit_first(
+                                    s.as_ptr(),
+                                    s.len(),
+                                ) {
                                     return; // just being checked (no data to write)
                                 }
                                 assert!(!signal::is_ptr_signal_check_flow_is_signal_first(
-                                    s.as_ptr()
+                                    s.as_ptr(),
+                                    s.len()
                                 ));
                             }
 
@@ -431,17 +1095,21 @@ impl<H: Hasher, const PF: ProtocolFlags> Hasher for SignalledInjectionHasher<H,
                     }
                     Flow::SignalFirst => {
                         #[cfg(any(feature = "mx", feature = "ndd"))]
-                        if signal::is_ptr_signal_hash(s.as_ptr()) {
-                            self.state.assert_nothing_written();
-                            self.state.set_signalled_proposal_coming(PF);
+                        if signal::is_ptr_signal_hash(s.as_ptr(), s.len()) {
+                            self.signal_seen();
+                            self.signalled_first();
                         } else {
                             #[cfg(feature = "chk-flow")]
                             {
-                                if signal::is_ptr_signal_check_flow_is_signal_first(s.as_ptr()) {
+                                if signal::is_ptr_signal_check_flow_is_signal_first(
+                                    s.as_ptr(),
+                                    s.len(),
+                                ) {
                                     return; // just being checked (no data to write)
                                 }
                                 assert!(!signal::is_ptr_signal_check_flow_is_submit_first(
-                                    s.as_ptr()
+                                    s.as_ptr(),
+                                    s.len()
                                 ));
                             }
 
@@ -473,9 +1141,87 @@ pub struct SignalledInjectionBuildHasher<
 impl<H: Hasher, B: BuildHasher<Hasher = H>, const PF: ProtocolFlags>
     SignalledInjectionBuildHasher<H, B, PF>
 {
-    pub fn new(build: B) -> Self {
+    /// With `chk`, an invalid `PF`, or a `PF` whose protocol needs cargo features that are not
+    /// enabled, fails to compile (rather than panicking at runtime).
+    pub const fn new(build: B) -> Self {
+        #[cfg(feature = "chk")]
+        const {
+            flags::assert_usable(PF)
+        };
         Self { build }
     }
+    /// Build a [Hasher], inject `injected` into it (as per protocol `PF`) and return the result of
+    /// [Hasher::finish] - which is `injected` itself. A one-line check that the protocol yields
+    /// back the injected hash.
+    ///
+    /// ```
+    /// use core::hash::BuildHasherDefault;
+    /// use hash_injector::{ProtocolFlags, SignalledInjectionBuildHasher, new};
+    /// use std::hash::DefaultHasher;
+    ///
+    /// const PF: ProtocolFlags = new::u8_stream::signal_first::u64();
+    /// let build = SignalledInjectionBuildHasher::<_, _, PF>::new(BuildHasherDefault::<
+    ///     DefaultHasher,
+    /// >::default());
+    /// assert_eq!(build.hash_one_injected(42), 42);
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn hash_one_injected(&self, injected: u64) -> u64 {
+        let mut hasher = self.build_hasher();
+        crate::inject::<_, PF>(&mut hasher, injected);
+        hasher.finish()
+    }
+}
+#[cfg(feature = "std")]
+impl<const PF: ProtocolFlags> SignalledInjectionBuildHasher<DefaultHasher, RandomState, PF> {
+    /// Wrap a new [RandomState] - the same (randomly seeded) [BuildHasher] as used by default by
+    /// [std::collections::HashMap].
+    ///
+    /// ```
+    /// # #[cfg(feature = "hpe")]
+    /// # {
+    /// use core::hash::{BuildHasher, Hasher};
+    /// use hash_injector::{ProtocolFlags, SignalledInjectionBuildHasher, inject, new};
+    ///
+    /// const PF: ProtocolFlags = new::len::signal_first::u64();
+    /// let build = SignalledInjectionBuildHasher::<_, _, PF>::random();
+    /// let mut hasher = build.build_hasher();
+    /// inject::<_, PF>(&mut hasher, 42);
+    /// assert_eq!(hasher.finish(), 42);
+    /// # }
+    /// ```
+    pub fn random() -> Self {
+        Self::new(RandomState::new())
+    }
+}
+#[cfg(feature = "std")]
+impl<const PF: ProtocolFlags>
+    SignalledInjectionBuildHasher<DefaultHasher, BuildHasherDefault<DefaultHasher>, PF>
+{
+    /// Wrap a [BuildHasherDefault] of [DefaultHasher]. Its [Hasher]s are NOT randomly seeded, so
+    /// ordinary (not injected) hashes are the same across instances. Do NOT use it for data
+    /// controlled by third parties (Hash DoS).
+    ///
+    /// ```
+    /// # #[cfg(feature = "hpe")]
+    /// # {
+    /// use core::hash::{BuildHasher, Hasher};
+    /// use hash_injector::{ProtocolFlags, SignalledInjectionBuildHasher, inject, new};
+    ///
+    /// const PF: ProtocolFlags = new::len::submit_first::u64();
+    /// let build = SignalledInjectionBuildHasher::<_, _, PF>::deterministic();
+    /// let mut hasher = build.build_hasher();
+    /// inject::<_, PF>(&mut hasher, 42);
+    /// assert_eq!(hasher.finish(), 42);
+    ///
+    /// let other = SignalledInjectionBuildHasher::<_, _, PF>::deterministic();
+    /// assert_eq!(build.hash_one("ordinary"), other.hash_one("ordinary"));
+    /// # }
+    /// ```
+    pub fn deterministic() -> Self {
+        Self::new(BuildHasherDefault::default())
+    }
 }
 impl<H: Hasher, B: BuildHasher<Hasher = H>, const PF: ProtocolFlags> BuildHasher
     for SignalledInjectionBuildHasher<H, B, PF>
@@ -487,3 +1233,1360 @@ impl<H: Hasher, B: BuildHasher<Hasher = H>, const PF: ProtocolFlags> BuildHasher
         SignalledInjectionHasher::new(self.build.build_hasher())
     }
 }
+
+#[cfg(all(test, feature = "hpe"))]
+mod tests {
+    use super::*;
+    use std::hash::RandomState;
+
+    const HASHES: [u64; 5] = [0, 1, 0x0123_4567_89AB_CDEF, u64::MAX - 1, u64::MAX];
+
+    fn inject_and_finish_stream<const PF: ProtocolFlags>() {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut hasher = build.build_hasher();
+        for hash in HASHES {
+            assert_eq!(hasher.inject_and_finish(hash), hash);
+        }
+    }
+
+    #[test]
+    fn inject_and_finish_signal_first() {
+        inject_and_finish_stream::<{ crate::new::len::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn inject_and_finish_submit_first() {
+        inject_and_finish_stream::<{ crate::new::len::submit_first::u64() }>();
+    }
+
+    /// [PossiblySubmitResult] (of submit-first protocols) has `Drop` glue only with the guard.
+    #[test]
+    fn drop_guard() {
+        assert_eq!(
+            core::mem::needs_drop::<PossiblySubmitResult>(),
+            cfg!(all(debug_assertions, not(feature = "no-drop-guard")))
+        );
+    }
+
+    fn finish_checked_ok<const PF: ProtocolFlags>() {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+
+        let mut injected = build.build_hasher();
+        crate::inject::<_, PF>(&mut injected, 0x0123_4567_89AB_CDEF);
+        assert_eq!(injected.finish_checked(), Ok(0x0123_4567_89AB_CDEF));
+
+        let mut ordinary = build.build_hasher();
+        ordinary.write_u8(1);
+        assert_eq!(ordinary.finish_checked(), Ok(ordinary.finish()));
+    }
+
+    #[test]
+    fn finish_checked_ok_signal_first() {
+        finish_checked_ok::<{ crate::new::len::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn finish_checked_ok_submit_first() {
+        finish_checked_ok::<{ crate::new::len::submit_first::u64() }>();
+    }
+
+    #[cfg(all(feature = "chk-mixing", debug_assertions))]
+    #[test]
+    #[should_panic(expected = "Partial signalling")]
+    fn chk_mixing_detects_signal_without_hash() {
+        const PF: ProtocolFlags = crate::new::len::signal_first::u64();
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut hasher = build.build_hasher();
+        hasher.write_length_prefix(len_signal_hash(PF));
+        let _ = hasher.finish();
+    }
+
+    #[cfg(all(feature = "chk-mixing", debug_assertions, not(feature = "chk")))]
+    #[test]
+    #[should_panic(expected = "Partial signalling")]
+    fn chk_mixing_detects_ordinary_data_after_injection() {
+        const PF: ProtocolFlags = crate::new::len::submit_first::u64();
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut hasher = build.build_hasher();
+        crate::inject::<_, PF>(&mut hasher, 0x0123_4567_89AB_CDEF);
+        hasher.write_u8(1);
+        let _ = hasher.finish();
+    }
+
+    /// Graceful passthrough: Keys that do not inject are hashed exactly as by the wrapped hasher.
+    fn passthrough<const PF: ProtocolFlags>() {
+        use std::hash::{BuildHasherDefault, DefaultHasher};
+        use std::string::String;
+        use std::vec;
+
+        let plain = BuildHasherDefault::<DefaultHasher>::default();
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(plain.clone());
+
+        assert_eq!(
+            build.hash_one(0x0123_4567_89AB_CDEFu64),
+            plain.hash_one(0x0123_4567_89AB_CDEFu64)
+        );
+        assert_eq!(build.hash_one(-1i128), plain.hash_one(-1i128));
+        assert_eq!(build.hash_one("str"), plain.hash_one("str"));
+        assert_eq!(build.hash_one(String::new()), plain.hash_one(String::new()));
+        assert_eq!(
+            build.hash_one((1u8, 2u64, "three")),
+            plain.hash_one((1u8, 2u64, "three"))
+        );
+        assert_eq!(
+            build.hash_one(vec![1u64, 2, 3]),
+            plain.hash_one(vec![1u64, 2, 3])
+        );
+        // Nothing written at all.
+        #[cfg(not(feature = "chk-empty"))]
+        assert_eq!(build.hash_one(()), plain.hash_one(()));
+    }
+
+    #[test]
+    fn passthrough_signal_first() {
+        passthrough::<{ crate::new::len::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn passthrough_submit_first() {
+        passthrough::<{ crate::new::len::submit_first::u64() }>();
+    }
+
+    /// `&mut SignalledInjectionHasher` forwards [Hasher::write_length_prefix] (used by `len`
+    /// protocols), too.
+    #[test]
+    fn through_mut_ref_len() {
+        fn inject_into<H: Hasher, const PF: ProtocolFlags>(mut hasher: H, hash: u64) {
+            crate::inject::<_, PF>(&mut hasher, hash);
+        }
+        fn check<const PF: ProtocolFlags>() {
+            let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+            let mut hasher = build.build_hasher();
+            inject_into::<_, PF>(&mut hasher, 0x0123_4567_89AB_CDEF);
+            assert_eq!(hasher.finish(), 0x0123_4567_89AB_CDEF);
+        }
+        check::<{ crate::new::len::signal_first::u64() }>();
+        check::<{ crate::new::len::submit_first::u128() }>();
+        check::<{ crate::new::len_cookie::submit_first::u64() }>();
+    }
+
+    /// Submit-first: A possibly submitted hash, followed by ordinary data (rather than the signal),
+    /// is ordinary data, too - and the wrapped hasher has received all of it.
+    fn possibly_submitted_then_data<const PF: ProtocolFlags>() {
+        use std::hash::{BuildHasherDefault, DefaultHasher};
+
+        let writes: [crate::testing::Writes; 3] = [
+            &|h| {
+                crate::signal::submit_hash(PF, h, 0x0123_4567_89AB_CDEF);
+                h.write_u8(1);
+            },
+            &|h| {
+                crate::signal::submit_hash(PF, h, 1);
+                crate::signal::submit_hash(PF, h, 2);
+            },
+            &|h| {
+                crate::signal::submit_hash(PF, h, 3);
+                h.write_length_prefix(0);
+            },
+        ];
+        crate::testing::assert_passthrough::<_, PF>(
+            BuildHasherDefault::<DefaultHasher>::default(),
+            &writes,
+        );
+    }
+
+    #[test]
+    fn possibly_submitted_then_data_len() {
+        possibly_submitted_then_data::<{ crate::new::len::submit_first::u64() }>();
+        possibly_submitted_then_data::<{ crate::new::len::submit_first::i128() }>();
+    }
+
+    #[test]
+    fn possibly_submitted_then_data_u8_stream() {
+        possibly_submitted_then_data::<{ crate::new::u8_stream::submit_first::u64() }>();
+        possibly_submitted_then_data::<{ crate::new::u8_stream::submit_first::bytes() }>();
+    }
+
+    fn build_hasher_default_in_a_map<const PF: ProtocolFlags>() {
+        use std::collections::HashMap;
+        use std::hash::{BuildHasherDefault, DefaultHasher};
+
+        #[derive(PartialEq, Eq)]
+        struct Injecting<const PF: ProtocolFlags>(u64);
+        impl<const PF: ProtocolFlags> core::hash::Hash for Injecting<PF> {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                crate::inject::<_, PF>(state, self.0);
+            }
+        }
+
+        let mut map = HashMap::<
+            _,
+            _,
+            BuildHasherDefault<SignalledInjectionHasher<DefaultHasher, PF>>,
+        >::default();
+        map.insert(Injecting::<PF>(1), "one");
+        map.insert(Injecting::<PF>(2), "two");
+        assert_eq!(map.hasher().hash_one(Injecting::<PF>(2)), 2);
+        assert_eq!(map.get(&Injecting::<PF>(1)), Some(&"one"));
+        assert_eq!(map.get(&Injecting::<PF>(3)), None);
+    }
+
+    #[test]
+    fn build_hasher_default_in_a_map_signal_first() {
+        build_hasher_default_in_a_map::<{ crate::new::len::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn build_hasher_default_in_a_map_submit_first() {
+        build_hasher_default_in_a_map::<{ crate::new::len::submit_first::u64() }>();
+    }
+
+    /// A clean signal-first `len` injection (and finish) makes zero calls to the underlying hasher.
+    #[test]
+    fn signal_first_len_leaves_underlying_hasher_untouched() {
+        use crate::testing::RecordingHasher;
+        const PF: ProtocolFlags = crate::new::len::signal_first::u64();
+
+        let mut recording = RecordingHasher::default();
+        {
+            let mut hasher = SignalledInjectionHasher::<_, PF>::borrow(&mut recording);
+            crate::inject::<_, PF>(&mut hasher, 0x0123_4567_89AB_CDEF);
+            assert_eq!(hasher.finish(), 0x0123_4567_89AB_CDEF);
+        }
+        assert_eq!(recording.calls, []);
+    }
+
+    #[cfg(feature = "chk-empty")]
+    #[test]
+    #[should_panic(expected = "nothing was written")]
+    fn chk_empty_detects_finish_without_injection() {
+        const PF: ProtocolFlags = crate::new::len::submit_first::u64();
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let hasher = build.build_hasher();
+        let _ = hasher.finish();
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "Signalled twice")]
+    fn signalled_twice_submit_first() {
+        const PF: ProtocolFlags = crate::new::len::submit_first::u64();
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut hasher = build.build_hasher();
+        crate::inject::<_, PF>(&mut hasher, 0x0123_4567_89AB_CDEF);
+        hasher.write_length_prefix(len_signal_hash(PF));
+    }
+
+    /// With `chk`, a different assertion catches this first.
+    #[cfg(all(debug_assertions, not(feature = "chk")))]
+    #[test]
+    #[should_panic(expected = "Signalled twice")]
+    fn signalled_twice_signal_first() {
+        const PF: ProtocolFlags = crate::new::len::signal_first::u64();
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut hasher = build.build_hasher();
+        crate::inject::<_, PF>(&mut hasher, 0x0123_4567_89AB_CDEF);
+        hasher.write_length_prefix(len_signal_hash(PF));
+    }
+
+    #[test]
+    fn finish_checked_signalled_but_not_submitted() {
+        const PF: ProtocolFlags = crate::new::len::signal_first::u64();
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut hasher = build.build_hasher();
+        hasher.write_length_prefix(len_signal_hash(PF));
+        assert_eq!(
+            hasher.finish_checked(),
+            Err(FinishError::SignalledButNotSubmitted)
+        );
+    }
+
+    #[test]
+    fn other_len_protocols_signal_is_ordinary_data() {
+        const PF: ProtocolFlags = crate::new::len::signal_first::u64();
+        const OTHER: ProtocolFlags = crate::new::len::signal_first::i64();
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut hasher = build.build_hasher();
+        hasher.write_length_prefix(len_signal_hash(OTHER));
+        hasher.write_u64(42);
+        assert_ne!(hasher.finish(), 42);
+    }
+
+    /// A length equal to the check-flow sentinel of the protocol's own flow, but outside of a
+    /// check-flow sequence, is ordinary data.
+    #[cfg(feature = "chk-flow")]
+    fn check_flow_sentinel_as_data<const PF: ProtocolFlags>(len: usize) {
+        use std::hash::{BuildHasherDefault, DefaultHasher};
+        let build = BuildHasherDefault::<DefaultHasher>::default();
+        let injection_build = SignalledInjectionBuildHasher::<_, _, PF>::new(build.clone());
+
+        let mut plain = build.build_hasher();
+        plain.write_length_prefix(len);
+        plain.write_u8(1);
+
+        let mut hasher = injection_build.build_hasher();
+        hasher.write_length_prefix(len);
+        hasher.write_u8(1);
+        assert_eq!(hasher.finish(), plain.finish());
+    }
+
+    #[cfg(feature = "chk-flow")]
+    #[test]
+    fn check_flow_sentinel_as_data_signal_first() {
+        use crate::signal::LEN_SIGNAL_CHECK_FLOW_IS_SIGNAL_FIRST;
+        check_flow_sentinel_as_data::<{ crate::new::len::signal_first::u64() }>(
+            LEN_SIGNAL_CHECK_FLOW_IS_SIGNAL_FIRST,
+        );
+    }
+
+    #[cfg(feature = "chk-flow")]
+    #[test]
+    fn check_flow_sentinel_as_data_submit_first() {
+        use crate::signal::LEN_SIGNAL_CHECK_FLOW_IS_SUBMIT_FIRST;
+        check_flow_sentinel_as_data::<{ crate::new::len::submit_first::u64() }>(
+            LEN_SIGNAL_CHECK_FLOW_IS_SUBMIT_FIRST,
+        );
+    }
+
+    /// A `Hash` implementation that submits first, hashed by a signal-first [Hasher]: Its signal is
+    /// not recognized (it differs per protocol), but its check-flow sentinel is. (With `chk-hash`,
+    /// [crate::inject]'s check of [Hasher::finish] fails first.)
+    #[cfg(all(feature = "chk-flow", not(feature = "chk-hash")))]
+    #[test]
+    #[should_panic(expected = "submits first, but the protocol signals first")]
+    fn chk_flow_detects_submit_first_in_signal_first() {
+        const PF: ProtocolFlags = crate::new::len::signal_first::u64();
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut hasher = build.build_hasher();
+        crate::inject::<_, { crate::new::len::submit_first::u64() }>(&mut hasher, 42);
+    }
+}
+
+#[cfg(test)]
+mod tests_trusted {
+    use super::*;
+    use std::hash::RandomState;
+
+    fn trusted_round_trip<const PF: ProtocolFlags>() {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+
+        let mut hasher = build.build_hasher();
+        crate::inject::<_, PF>(&mut hasher, 0x0123_4567_89AB_CDEF);
+        assert_eq!(hasher.finish(), 0x0123_4567_89AB_CDEF);
+        assert_eq!(hasher.finish_checked(), Ok(0x0123_4567_89AB_CDEF));
+
+        for hash in [0, 1, u64::MAX] {
+            assert_eq!(hasher.inject_and_finish(hash), hash);
+        }
+    }
+
+    #[test]
+    fn trusted_u64() {
+        trusted_round_trip::<{ crate::new::trusted::u64() }>();
+    }
+
+    #[test]
+    fn trusted_i64() {
+        trusted_round_trip::<{ crate::new::trusted::i64() }>();
+    }
+
+    #[test]
+    fn trusted_u128() {
+        trusted_round_trip::<{ crate::new::trusted::u128() }>();
+    }
+
+    #[test]
+    fn trusted_i128() {
+        trusted_round_trip::<{ crate::new::trusted::i128() }>();
+    }
+
+    #[test]
+    fn trusted_leaves_underlying_hasher_untouched() {
+        use crate::testing::RecordingHasher;
+        const PF: ProtocolFlags = crate::new::trusted::u64();
+
+        let mut recording = RecordingHasher::default();
+        {
+            let mut hasher = SignalledInjectionHasher::<_, PF>::borrow(&mut recording);
+            crate::inject::<_, PF>(&mut hasher, 0x0123_4567_89AB_CDEF);
+            assert_eq!(hasher.finish(), 0x0123_4567_89AB_CDEF);
+        }
+        assert_eq!(recording.calls, []);
+    }
+
+    #[test]
+    fn trusted_borrowed_hasher() {
+        use std::hash::{BuildHasherDefault, DefaultHasher};
+        const PF: ProtocolFlags = crate::new::trusted::u64();
+
+        let mut original = DefaultHasher::new();
+        original.write_u8(1);
+        {
+            let mut borrowed = SignalledInjectionHasher::<_, PF>::borrow(&mut original);
+            crate::inject::<_, PF>(&mut borrowed, 0x0123_4567_89AB_CDEF);
+            assert_eq!(borrowed.finish(), 0x0123_4567_89AB_CDEF);
+        }
+        original.write_u8(2);
+
+        let mut plain = BuildHasherDefault::<DefaultHasher>::default().build_hasher();
+        plain.write_u8(1);
+        plain.write_u8(2);
+        assert_eq!(original.finish(), plain.finish());
+    }
+
+    #[cfg(feature = "chk")]
+    #[test]
+    #[should_panic(expected = "NothingWritten")]
+    fn trusted_second_write() {
+        const PF: ProtocolFlags = crate::new::trusted::u64();
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut hasher = build.build_hasher();
+        crate::inject::<_, PF>(&mut hasher, 0x0123_4567_89AB_CDEF);
+        hasher.write_u64(1);
+    }
+
+    /// With `chk`, the panic location is the caller's (`#[track_caller]`), rather than this crate.
+    /// The test runs itself in a child process, where the panic reaches the default panic hook
+    /// (which reports the location), so that it doesn't replace the hook of concurrent tests.
+    #[cfg(feature = "chk")]
+    #[test]
+    fn trusted_second_write_panic_location() {
+        use std::format;
+        use std::process::Command;
+        use std::string::String;
+
+        const CHILD: &str = "HASH_INJECTOR_TEST_PANIC_LOCATION";
+        const PF: ProtocolFlags = crate::new::trusted::u64();
+        let line = line!() + 5;
+        if std::env::var_os(CHILD).is_some() {
+            let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+            let mut hasher = build.build_hasher();
+            crate::inject::<_, PF>(&mut hasher, 0x0123_4567_89AB_CDEF);
+            hasher.write_u64(1);
+            return;
+        }
+
+        let (_crate, module) = module_path!().split_once("::").unwrap();
+        let output = Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "--nocapture"])
+            .arg(format!("{module}::trusted_second_write_panic_location"))
+            .env(CHILD, "1")
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains(&format!("panicked at {}:{line}:", file!())),
+            "{stderr}"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_u8_stream {
+    use super::*;
+    use crate::signal::U8_STREAM_SIGNAL_HASH;
+    use crate::testing::{U8OnlyHasher, Writes, assert_passthrough, assert_round_trip};
+    use core::hash::BuildHasherDefault;
+
+    fn round_trip<const PF: ProtocolFlags>() {
+        assert_round_trip::<_, PF>(
+            BuildHasherDefault::<U8OnlyHasher>::default(),
+            &[0, 1, 0x0123_4567_89AB_CDEF, u64::MAX],
+        );
+    }
+
+    /// A plain [crate::inject] after ordinary data (for example, after a raw `Option<T>` wrote its
+    /// discriminant) is a misuse: Only [crate::PartialInject] combines them. `chk` reports it.
+    /// Otherwise the ordinary data is discarded.
+    fn ordinary_data_before_inject<const PF: ProtocolFlags>() -> u64 {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(BuildHasherDefault::<
+            U8OnlyHasher,
+        >::default());
+        let mut hasher = build.build_hasher();
+        hasher.write_u8(1);
+        crate::inject::<_, PF>(&mut hasher, 42);
+        hasher.finish()
+    }
+
+    #[cfg(feature = "chk")]
+    #[test]
+    #[should_panic(expected = "Only PartialInject may combine them")]
+    fn chk_detects_ordinary_data_before_inject() {
+        ordinary_data_before_inject::<{ crate::new::u8_stream::signal_first::u64() }>();
+    }
+
+    #[cfg(not(feature = "chk"))]
+    #[test]
+    fn ordinary_data_before_inject_is_discarded() {
+        assert_eq!(
+            ordinary_data_before_inject::<{ crate::new::u8_stream::signal_first::u64() }>(),
+            42
+        );
+    }
+
+    #[test]
+    fn round_trip_signal_first() {
+        round_trip::<{ crate::new::u8_stream::signal_first::u64() }>();
+        round_trip::<{ crate::new::u8_stream::signal_first::i128() }>();
+    }
+
+    #[test]
+    fn round_trip_submit_first() {
+        round_trip::<{ crate::new::u8_stream::submit_first::u64() }>();
+        round_trip::<{ crate::new::u8_stream::submit_first::i128() }>();
+    }
+
+    #[test]
+    fn write_injected() {
+        fn check<const PF: ProtocolFlags>() {
+            for hash in [0, 1, 0x0123_4567_89AB_CDEF, u64::MAX] {
+                let mut hasher = SignalledInjectionHasher::<U8OnlyHasher, PF>::default();
+                hasher.write_injected(hash);
+                assert_eq!(hasher.finish(), hash);
+            }
+        }
+        check::<{ crate::new::u8_stream::signal_first::u64() }>();
+        check::<{ crate::new::u8_stream::submit_first::bytes() }>();
+    }
+
+    #[test]
+    fn hash_one_injected() {
+        fn check<const PF: ProtocolFlags>() {
+            let build = SignalledInjectionBuildHasher::<_, _, PF>::new(BuildHasherDefault::<
+                U8OnlyHasher,
+            >::default());
+            for hash in [0, 1, 0x0123_4567_89AB_CDEF, u64::MAX] {
+                assert_eq!(build.hash_one_injected(hash), hash);
+            }
+        }
+        check::<{ crate::new::u8_stream::signal_first::u64() }>();
+        check::<{ crate::new::u8_stream::submit_first::i128() }>();
+    }
+
+    /// `&mut SignalledInjectionHasher` is a [Hasher], too (thanks to `impl Hasher for &mut H`), and
+    /// it shares the signalling state with the original.
+    #[test]
+    fn through_mut_ref() {
+        fn inject_into<H: Hasher, const PF: ProtocolFlags>(mut hasher: H, hash: u64) {
+            crate::inject::<_, PF>(&mut hasher, hash);
+        }
+        fn check<const PF: ProtocolFlags>() {
+            for hash in [0, 1, 0x0123_4567_89AB_CDEF, u64::MAX] {
+                let mut hasher = SignalledInjectionHasher::<U8OnlyHasher, PF>::default();
+                inject_into::<_, PF>(&mut hasher, hash);
+                assert_eq!(hasher.finish(), hash);
+            }
+        }
+        check::<{ crate::new::u8_stream::signal_first::u64() }>();
+        check::<{ crate::new::u8_stream::submit_first::i128() }>();
+        check::<{ crate::new::u8_stream::submit_first::bytes() }>();
+    }
+
+    /// Partial signals (followed by other bytes, other writes, or nothing) are ordinary data.
+    fn partial_signals_are_data<const PF: ProtocolFlags>() {
+        let writes: [Writes; 4] = [
+            &|h| {
+                for &byte in &U8_STREAM_SIGNAL_HASH[..3] {
+                    h.write_u8(byte);
+                }
+                h.write_u8(0);
+            },
+            &|h| {
+                for &byte in &U8_STREAM_SIGNAL_HASH[..7] {
+                    h.write_u8(byte);
+                }
+                h.write_u64(1);
+            },
+            &|h| {
+                for &byte in &U8_STREAM_SIGNAL_HASH[..5] {
+                    h.write_u8(byte);
+                }
+            },
+            // A partial signal, immediately followed by one more (still partial) signal.
+            &|h| {
+                h.write_u8(U8_STREAM_SIGNAL_HASH[0]);
+                h.write_u8(U8_STREAM_SIGNAL_HASH[0]);
+                h.write_u8(U8_STREAM_SIGNAL_HASH[1]);
+                h.write_u32(2);
+            },
+        ];
+        assert_passthrough::<_, PF>(BuildHasherDefault::<U8OnlyHasher>::default(), &writes);
+    }
+
+    #[test]
+    fn partial_signals_are_data_signal_first() {
+        partial_signals_are_data::<{ crate::new::u8_stream::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn partial_signals_are_data_submit_first() {
+        partial_signals_are_data::<{ crate::new::u8_stream::submit_first::u64() }>();
+    }
+}
+
+#[cfg(all(test, feature = "hpe"))]
+mod tests_len_cookie {
+    use super::*;
+    use crate::signal::LEN_COOKIE_SIGNAL_HASH;
+    use crate::testing::{Writes, assert_passthrough, assert_round_trip};
+    use std::hash::{BuildHasherDefault, DefaultHasher, Hash};
+
+    fn round_trip<const PF: ProtocolFlags>() {
+        assert_round_trip::<_, PF>(
+            BuildHasherDefault::<DefaultHasher>::default(),
+            &[0, 1, LEN_COOKIE_SIGNAL_HASH, u64::MAX],
+        );
+    }
+
+    #[test]
+    fn round_trip_signal_first() {
+        round_trip::<{ crate::new::len_cookie::signal_first::u64() }>();
+        round_trip::<{ crate::new::len_cookie::signal_first::i128() }>();
+    }
+
+    #[test]
+    fn round_trip_submit_first() {
+        round_trip::<{ crate::new::len_cookie::submit_first::u64() }>();
+        round_trip::<{ crate::new::len_cookie::submit_first::i128() }>();
+    }
+
+    /// Genuine zero lengths (for example, of empty slices) followed by real data (or by nothing)
+    /// are ordinary data. So are lengths of `usize::MAX`, which `len` protocols reserve.
+    fn zero_lengths_are_data<const PF: ProtocolFlags>() {
+        let writes: [Writes; 6] = [
+            &|h| {
+                h.write_length_prefix(0);
+                h.write_u64(1);
+            },
+            &|h| {
+                h.write_length_prefix(0);
+                h.write_length_prefix(0);
+                h.write_u8(2);
+            },
+            &|h| h.write_length_prefix(0),
+            // The cookie, but not right after a zero length.
+            &|h| {
+                h.write_length_prefix(0);
+                h.write_u32(3);
+                h.write_u64(LEN_COOKIE_SIGNAL_HASH);
+            },
+            &|h| h.write_length_prefix(usize::MAX),
+            // Keys that hash empty collections among other fields.
+            &|mut h| (Vec::<u8>::new(), 4u64, "", [0u16; 0]).hash(&mut h),
+        ];
+        assert_passthrough::<_, PF>(BuildHasherDefault::<DefaultHasher>::default(), &writes);
+    }
+
+    #[test]
+    fn zero_lengths_are_data_signal_first() {
+        zero_lengths_are_data::<{ crate::new::len_cookie::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn zero_lengths_are_data_submit_first() {
+        zero_lengths_are_data::<{ crate::new::len_cookie::submit_first::u64() }>();
+    }
+}
+
+#[cfg(all(test, feature = "hpe"))]
+mod tests_len_carrier {
+    use super::*;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    const PF: ProtocolFlags = crate::new::len_carrier::u64();
+
+    type Build =
+        SignalledInjectionBuildHasher<DefaultHasher, BuildHasherDefault<DefaultHasher>, PF>;
+
+    fn build() -> Build {
+        Build::new(BuildHasherDefault::default())
+    }
+
+    #[test]
+    fn round_trip() {
+        let build = build();
+        for hash in [
+            0,
+            1,
+            0xFFFF_FFFF,
+            1 << 32,
+            len_signal_hash(PF) as u64,
+            0x0123_4567_89AB_CDEF,
+            u64::MAX,
+        ] {
+            let mut hasher = build.build_hasher();
+            crate::inject::<_, PF>(&mut hasher, hash);
+            assert_eq!(hasher.finish(), hash);
+        }
+    }
+
+    /// The signal, then the high and the low 32 bits - all as lengths.
+    #[test]
+    fn carried_in_lengths() {
+        let mut hasher = build().build_hasher();
+        hasher.write_length_prefix(len_signal_hash(PF));
+        hasher.write_length_prefix(0x0123_4567);
+        hasher.write_length_prefix(0x89AB_CDEF);
+        assert_eq!(hasher.finish(), 0x0123_4567_89AB_CDEF);
+    }
+
+    /// Lengths that are not preceded by the signal are ordinary data.
+    #[test]
+    fn lengths_are_data() {
+        let plain_build = BuildHasherDefault::<DefaultHasher>::default();
+        let build = build();
+        let key = (Vec::<u8>::new(), [1u8, 2, 3], 4u64, "five");
+        assert_eq!(build.hash_one(&key), plain_build.hash_one(&key));
+
+        let mut plain = plain_build.build_hasher();
+        let mut hasher = build.build_hasher();
+        for len in [0, 0x0123_4567, usize::MAX] {
+            plain.write_length_prefix(len);
+            hasher.write_length_prefix(len);
+        }
+        assert_eq!(hasher.finish(), plain.finish());
+    }
+
+    #[test]
+    fn signalled_but_not_carried() {
+        let mut hasher = build().build_hasher();
+        hasher.write_length_prefix(len_signal_hash(PF));
+        hasher.write_length_prefix(0x0123_4567);
+        assert_eq!(
+            hasher.finish_checked(),
+            Err(FinishError::SignalledButNotSubmitted)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_usize {
+    use super::*;
+    use crate::signal::USIZE_COOKIE_SIGNAL_HASH;
+    use crate::testing::{Writes, assert_passthrough, assert_round_trip};
+    use std::hash::{BuildHasherDefault, DefaultHasher, Hash};
+    use std::vec;
+    use std::vec::Vec;
+
+    fn round_trip<const PF: ProtocolFlags>() {
+        assert_round_trip::<_, PF>(
+            BuildHasherDefault::<DefaultHasher>::default(),
+            &[0, 1, USIZE_COOKIE_SIGNAL_HASH, u64::MAX],
+        );
+    }
+
+    #[test]
+    fn round_trip_signal_first() {
+        round_trip::<{ crate::new::usize::signal_first::u64() }>();
+        round_trip::<{ crate::new::usize::signal_first::i128() }>();
+    }
+
+    #[test]
+    fn round_trip_submit_first() {
+        round_trip::<{ crate::new::usize::submit_first::u64() }>();
+        round_trip::<{ crate::new::usize::submit_first::i128() }>();
+    }
+
+    /// `usize::MAX` NOT followed right away by the cookie is ordinary data. So are collection
+    /// lengths (written with `write_usize`, or with `write_length_prefix`, which forwards to it
+    /// without `hpe`).
+    fn usize_values_are_data<const PF: ProtocolFlags>() {
+        let writes: [Writes; 5] = [
+            &|h| {
+                h.write_usize(usize::MAX);
+                h.write_u64(1);
+            },
+            &|h| {
+                h.write_usize(usize::MAX);
+                h.write_usize(usize::MAX);
+                h.write_u8(2);
+            },
+            &|h| h.write_usize(usize::MAX),
+            // The cookie, but not right after usize::MAX.
+            &|h| {
+                h.write_usize(usize::MAX);
+                h.write_u32(3);
+                h.write_u64(USIZE_COOKIE_SIGNAL_HASH);
+            },
+            // Keys that hash collections among other fields.
+            &|mut h| (vec![1u8, 2], Vec::<u16>::new(), usize::MAX, 4u64, "").hash(&mut h),
+        ];
+        assert_passthrough::<_, PF>(BuildHasherDefault::<DefaultHasher>::default(), &writes);
+    }
+
+    #[test]
+    fn usize_values_are_data_signal_first() {
+        usize_values_are_data::<{ crate::new::usize::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn usize_values_are_data_submit_first() {
+        usize_values_are_data::<{ crate::new::usize::submit_first::u64() }>();
+    }
+}
+
+/// Protocols that submit the hash as 8 little-endian bytes (with [Hasher::write]).
+#[cfg(test)]
+mod tests_bytes {
+    use super::*;
+    use crate::testing::{Writes, assert_passthrough, assert_round_trip};
+    use std::hash::{BuildHasherDefault, DefaultHasher, Hash};
+
+    fn round_trip<const PF: ProtocolFlags>() {
+        assert_round_trip::<_, PF>(
+            BuildHasherDefault::<DefaultHasher>::default(),
+            &[0, 1, 0x0123_4567_89AB_CDEF, u64::MAX],
+        );
+    }
+
+    #[test]
+    fn round_trip_signal_first() {
+        round_trip::<{ crate::new::u8_stream::signal_first::bytes() }>();
+        round_trip::<{ crate::new::usize::signal_first::bytes() }>();
+        #[cfg(any(feature = "mx", feature = "ndd"))]
+        round_trip::<{ crate::new::u8s::signal_first::bytes() }>();
+        #[cfg(feature = "hpe")]
+        round_trip::<{ crate::new::len::signal_first::bytes() }>();
+    }
+
+    #[test]
+    fn round_trip_submit_first() {
+        round_trip::<{ crate::new::u8_stream::submit_first::bytes() }>();
+        round_trip::<{ crate::new::usize::submit_first::bytes() }>();
+        #[cfg(any(feature = "mx", feature = "ndd"))]
+        round_trip::<{ crate::new::u8s::submit_first::bytes() }>();
+        #[cfg(feature = "hpe")]
+        round_trip::<{ crate::new::len::submit_first::bytes() }>();
+    }
+
+    #[test]
+    fn round_trip_trusted() {
+        round_trip::<{ crate::new::trusted::bytes() }>();
+    }
+
+    /// Byte writes (of 8 bytes, or of other lengths) without a signal are ordinary data.
+    fn bytes_are_data<const PF: ProtocolFlags>() {
+        let writes: [Writes; 5] = [
+            &|h| h.write(&7u64.to_le_bytes()),
+            &|h| {
+                h.write(&7u64.to_le_bytes());
+                h.write(&8u64.to_le_bytes());
+            },
+            &|h| {
+                h.write(&[1, 2, 3]);
+                h.write(&7u64.to_le_bytes());
+            },
+            &|h| h.write(&[0; 16]),
+            &|mut h| ([1u8; 8], "abcdefgh", 7u64.to_le_bytes()).hash(&mut h),
+        ];
+        assert_passthrough::<_, PF>(BuildHasherDefault::<DefaultHasher>::default(), &writes);
+    }
+
+    #[test]
+    fn bytes_are_data_signal_first() {
+        bytes_are_data::<{ crate::new::u8_stream::signal_first::bytes() }>();
+    }
+
+    #[test]
+    fn bytes_are_data_submit_first() {
+        bytes_are_data::<{ crate::new::u8_stream::submit_first::bytes() }>();
+    }
+}
+
+#[cfg(all(test, feature = "chk-width"))]
+mod tests_chk_width {
+    use super::*;
+    use crate::signal::U8_STREAM_SIGNAL_HASH;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+    use std::panic::{self, AssertUnwindSafe};
+
+    const HASH: u64 = 0x0123_4567_89AB_CDEF;
+
+    fn write_hash(hasher: &mut dyn Hasher, via: HashVia) {
+        match via {
+            HashVia::U64 => hasher.write_u64(HASH),
+            HashVia::I64 => hasher.write_i64(HASH as i64),
+            HashVia::U128 => hasher.write_u128(HASH as u128),
+            HashVia::I128 => hasher.write_i128(HASH as i128),
+            HashVia::Bytes => hasher.write(&HASH.to_le_bytes()),
+        }
+    }
+
+    /// Inject [HASH] as per `PF`, except for submitting it via `via`. Return the panic message, if
+    /// any.
+    fn panic_message<const PF: ProtocolFlags>(via: HashVia) -> Option<String> {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(BuildHasherDefault::<
+            DefaultHasher,
+        >::default());
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut hasher = build.build_hasher();
+            let signal = |hasher: &mut dyn Hasher| {
+                if flags::is_signal_via_u8_stream(PF) {
+                    for byte in U8_STREAM_SIGNAL_HASH {
+                        hasher.write_u8(byte);
+                    }
+                }
+            };
+            match flags::flow(PF) {
+                Flow::SignalFirst => {
+                    signal(&mut hasher);
+                    write_hash(&mut hasher, via);
+                }
+                Flow::SubmitFirst => {
+                    write_hash(&mut hasher, via);
+                    signal(&mut hasher);
+                }
+            }
+            assert_eq!(hasher.finish(), HASH);
+        }))
+        .err()
+        .map(|payload| {
+            payload
+                .downcast_ref::<String>()
+                .cloned()
+                .unwrap_or_default()
+        })
+    }
+
+    fn mismatches_panic<const PF: ProtocolFlags>() {
+        for via in [
+            HashVia::U64,
+            HashVia::I64,
+            HashVia::U128,
+            HashVia::I128,
+            HashVia::Bytes,
+        ] {
+            let message = panic_message::<PF>(via);
+            if via == flags::hash_via(PF) {
+                assert_eq!(message, None);
+            } else {
+                let message = message.unwrap();
+                assert!(
+                    message.contains("but the protocol submits it via"),
+                    "{message}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn trusted() {
+        mismatches_panic::<{ crate::new::trusted::u64() }>();
+        mismatches_panic::<{ crate::new::trusted::i64() }>();
+        mismatches_panic::<{ crate::new::trusted::u128() }>();
+        mismatches_panic::<{ crate::new::trusted::i128() }>();
+        mismatches_panic::<{ crate::new::trusted::bytes() }>();
+    }
+
+    #[test]
+    fn signal_first() {
+        mismatches_panic::<{ crate::new::u8_stream::signal_first::u64() }>();
+        mismatches_panic::<{ crate::new::u8_stream::signal_first::i64() }>();
+        mismatches_panic::<{ crate::new::u8_stream::signal_first::u128() }>();
+        mismatches_panic::<{ crate::new::u8_stream::signal_first::i128() }>();
+        mismatches_panic::<{ crate::new::u8_stream::signal_first::bytes() }>();
+    }
+
+    #[test]
+    fn submit_first() {
+        mismatches_panic::<{ crate::new::u8_stream::submit_first::u64() }>();
+        mismatches_panic::<{ crate::new::u8_stream::submit_first::i64() }>();
+        mismatches_panic::<{ crate::new::u8_stream::submit_first::u128() }>();
+        mismatches_panic::<{ crate::new::u8_stream::submit_first::i128() }>();
+        mismatches_panic::<{ crate::new::u8_stream::submit_first::bytes() }>();
+    }
+}
+
+/// The right signal, written with the wrong `Hasher::write_xxx`, for each [SignalVia] (except for
+/// `trusted` protocols, which don't signal).
+#[cfg(all(test, feature = "chk-signal-method"))]
+mod tests_chk_signal_method {
+    use super::*;
+    use std::hash::DefaultHasher;
+
+    const HASH: u64 = 0x0123_4567_89AB_CDEF;
+
+    fn hasher<const PF: ProtocolFlags>() -> SignalledInjectionHasher<DefaultHasher, PF> {
+        SignalledInjectionHasher::default()
+    }
+
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    #[test]
+    #[should_panic(expected = "written with Hasher::write_str, but protocol U8s")]
+    fn u8s_signal_via_write_str() {
+        let mut hasher = hasher::<{ crate::new::u8s::signal_first::u64() }>();
+        hasher.write_str(signal::str_signal_hash());
+        hasher.write_u64(HASH);
+    }
+
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    #[test]
+    #[should_panic(expected = "written with Hasher::write, but protocol Str")]
+    fn str_signal_via_write() {
+        let mut hasher = hasher::<{ crate::new::str::signal_first::u64() }>();
+        hasher.write(signal::u8s_signal_hash());
+        hasher.write_u64(HASH);
+    }
+
+    #[cfg(feature = "hpe")]
+    #[test]
+    #[should_panic(expected = "written with Hasher::write_usize, but protocol Len")]
+    fn len_signal_via_write_usize() {
+        const PF: ProtocolFlags = crate::new::len::signal_first::u64();
+        let mut hasher = hasher::<PF>();
+        hasher.write_usize(len_signal_hash(PF));
+        hasher.write_u64(HASH);
+    }
+
+    #[test]
+    #[should_panic(expected = "written with Hasher::write, but protocol U8Stream")]
+    fn u8_stream_signal_via_write() {
+        let mut hasher = hasher::<{ crate::new::u8_stream::signal_first::u64() }>();
+        hasher.write(&signal::U8_STREAM_SIGNAL_HASH);
+        hasher.write_u64(HASH);
+    }
+
+    #[cfg(feature = "hpe")]
+    #[test]
+    #[should_panic(expected = "wrong Hasher::write_xxx for its first half, but protocol LenCookie")]
+    fn len_cookie_signal_via_write_usize() {
+        const PF: ProtocolFlags = crate::new::len_cookie::signal_first::u64();
+        let mut hasher = hasher::<PF>();
+        hasher.write_usize(0);
+        hasher.write_u64(signal::cookie(PF));
+        hasher.write_u64(HASH);
+    }
+
+    #[cfg(feature = "hpe")]
+    #[test]
+    #[should_panic(expected = "wrong Hasher::write_xxx for its first half, but protocol Usize")]
+    fn usize_signal_via_write_length_prefix() {
+        const PF: ProtocolFlags = crate::new::usize::signal_first::u64();
+        let mut hasher = hasher::<PF>();
+        hasher.write_length_prefix(USIZE_SIGNAL_HASH);
+        hasher.write_u64(signal::cookie(PF));
+        hasher.write_u64(HASH);
+    }
+
+    /// Signals written with the right `Hasher::write_xxx` don't panic.
+    #[test]
+    fn right_method() {
+        let mut u8_stream = hasher::<{ crate::new::u8_stream::signal_first::u64() }>();
+        for byte in signal::U8_STREAM_SIGNAL_HASH {
+            u8_stream.write_u8(byte);
+        }
+        u8_stream.write_u64(HASH);
+        assert_eq!(u8_stream.finish(), HASH);
+
+        const PF: ProtocolFlags = crate::new::usize::signal_first::u64();
+        let mut usize = hasher::<PF>();
+        usize.write_usize(USIZE_SIGNAL_HASH);
+        usize.write_u64(signal::cookie(PF));
+        usize.write_u64(HASH);
+        assert_eq!(usize.finish(), HASH);
+    }
+}
+
+/// Adversarial sequences: all orders (up to a length) of signals, hashes and other writes.
+#[cfg(test)]
+mod tests_sequences {
+    use super::*;
+    use crate::testing::RecordingHasher;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::vec;
+    use std::vec::Vec;
+
+    const HASH: u64 = 0x0123_4567_89AB_CDEF;
+
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    enum Op {
+        Signal,
+        Hash,
+        U64,
+        U32,
+        #[cfg(feature = "hpe")]
+        ZeroLen,
+    }
+    const OPS: &[Op] = &[
+        Op::Signal,
+        Op::Hash,
+        Op::U64,
+        Op::U32,
+        #[cfg(feature = "hpe")]
+        Op::ZeroLen,
+    ];
+
+    fn apply<const PF: ProtocolFlags>(op: Op, hasher: &mut impl Hasher) {
+        match op {
+            Op::Signal => signal::signal(PF, hasher),
+            Op::Hash => signal::submit_hash(PF, hasher, HASH),
+            Op::U64 => hasher.write_u64(7),
+            Op::U32 => hasher.write_u32(7),
+            #[cfg(feature = "hpe")]
+            Op::ZeroLen => hasher.write_length_prefix(0),
+        }
+    }
+
+    /// All sequences of up to `max_len` operations.
+    fn sequences(max_len: usize) -> Vec<Vec<Op>> {
+        let mut all = vec![vec![]];
+        let mut longest = vec![vec![]];
+        for _ in 0..max_len {
+            longest = longest
+                .iter()
+                .flat_map(|seq: &Vec<Op>| {
+                    OPS.iter().map(move |&op| {
+                        let mut seq = seq.clone();
+                        seq.push(op);
+                        seq
+                    })
+                })
+                .collect();
+            all.extend(longest.iter().cloned());
+        }
+        all
+    }
+
+    /// The clean sequence (as per [crate::inject]) must inject the hash. Any other sequence that
+    /// signals may panic (in debug mode, or with `chk` and related features). If it doesn't, it
+    /// must not return the hash unless it was both signalled and submitted. Without a signal, it
+    /// must not panic, and all writes must pass through unchanged.
+    fn every_order<const PF: ProtocolFlags>() {
+        let clean = match flags::flow(PF) {
+            Flow::SignalFirst => [Op::Signal, Op::Hash],
+            Flow::SubmitFirst => [Op::Hash, Op::Signal],
+        };
+        for ops in sequences(3) {
+            let mut plain = RecordingHasher::default();
+            for &op in &ops {
+                apply::<PF>(op, &mut plain);
+            }
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                let mut hasher = SignalledInjectionHasher::<_, PF>::new(RecordingHasher::default());
+                for &op in &ops {
+                    apply::<PF>(op, &mut hasher);
+                }
+                (
+                    hasher.finish(),
+                    hasher.finish_checked(),
+                    hasher.hasher.calls,
+                )
+            }));
+            if ops == clean {
+                let (result, checked, _) = result.unwrap();
+                assert_eq!((result, checked), (HASH, Ok(HASH)));
+                continue;
+            }
+            // Ordinary data alone must never panic. (Except for finishing with nothing written,
+            // which `chk-empty` reports.)
+            if !ops.contains(&Op::Signal) && !(cfg!(feature = "chk-empty") && ops.is_empty()) {
+                assert!(result.is_ok(), "{ops:?}");
+            }
+            let Ok((result, checked, calls)) = result else {
+                continue;
+            };
+            if !(ops.contains(&Op::Signal) && ops.contains(&Op::Hash)) {
+                assert_ne!(result, HASH, "{ops:?}");
+            }
+            if !ops.contains(&Op::Signal) {
+                assert_eq!(calls, plain.calls, "{ops:?}");
+                assert_eq!(result, plain.finish(), "{ops:?}");
+            }
+            if ops == [Op::Signal] && flags::is_signal_first(PF) {
+                assert_eq!(checked, Err(FinishError::SignalledButNotSubmitted));
+            }
+        }
+    }
+
+    #[test]
+    fn every_order_u8_stream() {
+        every_order::<{ crate::new::u8_stream::signal_first::u64() }>();
+        every_order::<{ crate::new::u8_stream::submit_first::u64() }>();
+        every_order::<{ crate::new::u8_stream::submit_first::i128() }>();
+    }
+
+    #[test]
+    fn every_order_usize() {
+        every_order::<{ crate::new::usize::signal_first::u64() }>();
+        every_order::<{ crate::new::usize::submit_first::u64() }>();
+    }
+
+    #[cfg(feature = "hpe")]
+    #[test]
+    fn every_order_len() {
+        every_order::<{ crate::new::len::signal_first::u64() }>();
+        every_order::<{ crate::new::len::submit_first::u64() }>();
+        every_order::<{ crate::new::len_cookie::signal_first::u64() }>();
+        every_order::<{ crate::new::len_cookie::submit_first::u64() }>();
+    }
+
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    #[test]
+    fn every_order_u8s() {
+        every_order::<{ crate::new::u8s::signal_first::u64() }>();
+        every_order::<{ crate::new::u8s::submit_first::u64() }>();
+    }
+
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    #[test]
+    fn every_order_str() {
+        every_order::<{ crate::new::str::signal_first::u64() }>();
+        every_order::<{ crate::new::str::submit_first::u64() }>();
+    }
+}
+
+/// Signals of `u8s` and `str` protocols live in a `static` shared by all threads. Many threads
+/// building from one [SignalledInjectionBuildHasher] and injecting at once must not interfere.
+#[cfg(test)]
+mod tests_threads {
+    use super::*;
+    use std::hash::RandomState;
+    use std::thread;
+
+    const THREADS: u64 = 8;
+    const ROUNDS: u64 = 1000;
+
+    fn concurrent<const PF: ProtocolFlags>() {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        thread::scope(|scope| {
+            for t in 0..THREADS {
+                let build = &build;
+                scope.spawn(move || {
+                    for round in 0..ROUNDS {
+                        let hash = t << 32 | round;
+                        let mut hasher = build.build_hasher();
+                        crate::inject::<_, PF>(&mut hasher, hash);
+                        assert_eq!(hasher.finish(), hash);
+                    }
+                });
+            }
+        });
+    }
+
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    #[test]
+    fn u8s() {
+        concurrent::<{ crate::new::u8s::signal_first::u64() }>();
+        concurrent::<{ crate::new::u8s::submit_first::u64() }>();
+    }
+
+    #[cfg(all(feature = "hpe", any(feature = "mx", feature = "ndd")))]
+    #[test]
+    fn str() {
+        concurrent::<{ crate::new::str::signal_first::u64() }>();
+        concurrent::<{ crate::new::str::submit_first::u64() }>();
+    }
+
+    #[cfg(feature = "hpe")]
+    #[test]
+    fn len() {
+        concurrent::<{ crate::new::len::signal_first::u64() }>();
+        concurrent::<{ crate::new::len::submit_first::u64() }>();
+    }
+
+    #[test]
+    fn usize() {
+        concurrent::<{ crate::new::usize::signal_first::u64() }>();
+        concurrent::<{ crate::new::usize::submit_first::u64() }>();
+    }
+}
+
+/// Every [Hasher] method, called with ordinary data as the only write, is forwarded to the
+/// underlying hasher exactly once and as-is - except for the hash of a `trusted` protocol, which is
+/// received rather than forwarded. Any `write_xxx` that we forget to override would go through
+/// [Hasher]'s default (for example, `write_u16` through `write`), and this would catch it.
+#[cfg(test)]
+mod tests_methods {
+    use super::*;
+    use crate::testing::{Call, RecordingHasher, Writes, assert_passthrough};
+    use core::hash::BuildHasherDefault;
+    use std::vec;
+    use std::vec::Vec;
+
+    fn methods() -> Vec<(Writes<'static>, Call)> {
+        vec![
+            (&|h| h.write(b"ab"), Call::Write(b"ab".to_vec())),
+            (&|h| h.write_u8(7), Call::U8(7)),
+            (&|h| h.write_u16(7), Call::U16(7)),
+            (&|h| h.write_u32(7), Call::U32(7)),
+            (&|h| h.write_u64(7), Call::U64(7)),
+            (&|h| h.write_u128(7), Call::U128(7)),
+            (&|h| h.write_usize(7), Call::Usize(7)),
+            (&|h| h.write_i8(-7), Call::I8(-7)),
+            (&|h| h.write_i16(-7), Call::I16(-7)),
+            (&|h| h.write_i32(-7), Call::I32(-7)),
+            (&|h| h.write_i64(-7), Call::I64(-7)),
+            (&|h| h.write_i128(-7), Call::I128(-7)),
+            (&|h| h.write_isize(-7), Call::Isize(-7)),
+            #[cfg(feature = "hpe")]
+            (&|h| h.write_length_prefix(7), Call::LengthPrefix(7)),
+            #[cfg(feature = "hpe")]
+            (&|h| h.write_str("ab"), Call::Str("ab".into())),
+        ]
+    }
+
+    fn every_method<const PF: ProtocolFlags>() {
+        for (write, call) in methods() {
+            let mut recording = RecordingHasher::default();
+            let finish = {
+                let mut hasher = SignalledInjectionHasher::<_, PF>::borrow(&mut recording);
+                write(&mut hasher);
+                hasher.finish()
+            };
+            if flags::is_signal_via_trusted(PF) && call == Call::U64(7) {
+                assert_eq!(finish, 7);
+                assert_eq!(recording.calls, []);
+            } else {
+                assert_passthrough::<_, PF>(
+                    BuildHasherDefault::<RecordingHasher>::default(),
+                    &[write],
+                );
+                assert_eq!(recording.calls, [call]);
+            }
+        }
+    }
+
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    #[test]
+    fn u8s() {
+        every_method::<{ crate::new::u8s::signal_first::u64() }>();
+        every_method::<{ crate::new::u8s::submit_first::u64() }>();
+    }
+
+    #[cfg(all(feature = "hpe", any(feature = "mx", feature = "ndd")))]
+    #[test]
+    fn str() {
+        every_method::<{ crate::new::str::signal_first::u64() }>();
+        every_method::<{ crate::new::str::submit_first::u64() }>();
+    }
+
+    #[cfg(feature = "hpe")]
+    #[test]
+    fn len() {
+        every_method::<{ crate::new::len::signal_first::u64() }>();
+        every_method::<{ crate::new::len::submit_first::u64() }>();
+        every_method::<{ crate::new::len_cookie::signal_first::u64() }>();
+        every_method::<{ crate::new::len_cookie::submit_first::u64() }>();
+        every_method::<{ crate::new::len_carrier::u64() }>();
+    }
+
+    #[test]
+    fn u8_stream() {
+        every_method::<{ crate::new::u8_stream::signal_first::u64() }>();
+        every_method::<{ crate::new::u8_stream::submit_first::i128() }>();
+    }
+
+    #[test]
+    fn usize() {
+        every_method::<{ crate::new::usize::signal_first::u64() }>();
+        every_method::<{ crate::new::usize::submit_first::u64() }>();
+    }
+
+    /// With `chk-width`, a `trusted` protocol rejects a first write of another width than its hash.
+    #[cfg(not(feature = "chk-width"))]
+    #[test]
+    fn trusted() {
+        every_method::<{ crate::new::trusted::u64() }>();
+    }
+}
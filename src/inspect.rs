@@ -0,0 +1,179 @@
+use core::hash::Hasher;
+
+/// A [Hasher] that passes a description of each `write_xxx` call (like `write_u64(42)`) to a
+/// callback, and then forwards the call to the wrapped [Hasher]. For debugging signalling.
+///
+/// Where you put it decides what you see:
+/// - Wrap a [crate::SignalledInjectionHasher] in [InspectHasher] to see the exact sequence that a
+///   [core::hash::Hash] implementation writes - including the signal and the submitted hash.
+/// - Wrap your [Hasher] in [InspectHasher], and that in [crate::SignalledInjectionHasher], to see
+///   what reaches your [Hasher]: ordinary data, but also (depending on the protocol) bytes of
+///   `u8_stream` signals, both halves of `usize` and `len_cookie` signals, and hashes submitted by
+///   submit-first protocols. (Then your [Hasher]'s result is not used.)
+///
+/// [Hasher::finish] is forwarded, but not described.
+///
+/// ```
+/// use core::hash::Hasher;
+/// use hash_injector::{InspectHasher, ProtocolFlags, SignalledInjectionHasher, new};
+/// use std::hash::DefaultHasher;
+///
+/// const PF: ProtocolFlags = new::usize::signal_first::u64();
+/// let mut calls = Vec::new();
+/// let mut hasher = InspectHasher::new(
+///     SignalledInjectionHasher::<DefaultHasher, PF>::default(),
+///     |call: &str| calls.push(call.to_owned()),
+/// );
+/// hash_injector::inject::<_, PF>(&mut hasher, 42);
+/// assert_eq!(hasher.finish(), 42);
+/// drop(hasher);
+/// assert_eq!(calls.last().unwrap(), "write_u64(42)");
+/// ```
+pub struct InspectHasher<H: Hasher, F: FnMut(&str)> {
+    hasher: H,
+    inspect: F,
+}
+
+impl<H: Hasher, F: FnMut(&str)> InspectHasher<H, F> {
+    pub const fn new(hasher: H, inspect: F) -> Self {
+        Self { hasher, inspect }
+    }
+    pub fn into_inner(self) -> H {
+        self.hasher
+    }
+}
+
+impl<H: Hasher, F: FnMut(&str)> Hasher for InspectHasher<H, F> {
+    fn finish(&self) -> u64 {
+        self.hasher.finish()
+    }
+    fn write(&mut self, bytes: &[u8]) {
+        (self.inspect)(&format!("write({bytes:?})"));
+        self.hasher.write(bytes);
+    }
+    fn write_u8(&mut self, i: u8) {
+        (self.inspect)(&format!("write_u8({i})"));
+        self.hasher.write_u8(i);
+    }
+    fn write_u16(&mut self, i: u16) {
+        (self.inspect)(&format!("write_u16({i})"));
+        self.hasher.write_u16(i);
+    }
+    fn write_u32(&mut self, i: u32) {
+        (self.inspect)(&format!("write_u32({i})"));
+        self.hasher.write_u32(i);
+    }
+    fn write_u64(&mut self, i: u64) {
+        (self.inspect)(&format!("write_u64({i})"));
+        self.hasher.write_u64(i);
+    }
+    fn write_u128(&mut self, i: u128) {
+        (self.inspect)(&format!("write_u128({i})"));
+        self.hasher.write_u128(i);
+    }
+    fn write_usize(&mut self, i: usize) {
+        (self.inspect)(&format!("write_usize({i})"));
+        self.hasher.write_usize(i);
+    }
+    fn write_i8(&mut self, i: i8) {
+        (self.inspect)(&format!("write_i8({i})"));
+        self.hasher.write_i8(i);
+    }
+    fn write_i16(&mut self, i: i16) {
+        (self.inspect)(&format!("write_i16({i})"));
+        self.hasher.write_i16(i);
+    }
+    fn write_i32(&mut self, i: i32) {
+        (self.inspect)(&format!("write_i32({i})"));
+        self.hasher.write_i32(i);
+    }
+    fn write_i64(&mut self, i: i64) {
+        (self.inspect)(&format!("write_i64({i})"));
+        self.hasher.write_i64(i);
+    }
+    fn write_i128(&mut self, i: i128) {
+        (self.inspect)(&format!("write_i128({i})"));
+        self.hasher.write_i128(i);
+    }
+    fn write_isize(&mut self, i: isize) {
+        (self.inspect)(&format!("write_isize({i})"));
+        self.hasher.write_isize(i);
+    }
+    #[cfg(feature = "hpe")]
+    fn write_length_prefix(&mut self, len: usize) {
+        (self.inspect)(&format!("write_length_prefix({len})"));
+        self.hasher.write_length_prefix(len);
+    }
+    #[cfg(feature = "hpe")]
+    fn write_str(&mut self, s: &str) {
+        (self.inspect)(&format!("write_str({s:?})"));
+        self.hasher.write_str(s);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::{USIZE_COOKIE_SIGNAL_HASH, USIZE_SIGNAL_HASH};
+    use crate::{ProtocolFlags, SignalledInjectionHasher};
+    use std::hash::DefaultHasher;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+
+    #[test]
+    fn sees_signal_and_hash() {
+        const PF: ProtocolFlags = crate::new::usize::signal_first::u64();
+        let mut calls = Vec::<String>::new();
+        {
+            let mut hasher = InspectHasher::new(
+                SignalledInjectionHasher::<DefaultHasher, PF>::default(),
+                |call: &str| calls.push(call.to_string()),
+            );
+            crate::inject::<_, PF>(&mut hasher, 42);
+            assert_eq!(hasher.finish(), 42);
+        }
+        assert_eq!(
+            calls,
+            [
+                format!("write_usize({USIZE_SIGNAL_HASH})"),
+                format!("write_u64({USIZE_COOKIE_SIGNAL_HASH})"),
+                "write_u64(42)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn sees_ordinary_data_inside() {
+        const PF: ProtocolFlags = crate::new::usize::submit_first::u64();
+        let mut calls = Vec::<String>::new();
+        {
+            let mut hasher = SignalledInjectionHasher::<_, PF>::from(InspectHasher::new(
+                DefaultHasher::new(),
+                |call: &str| calls.push(call.to_string()),
+            ));
+            hasher.write(b"ab");
+            hasher.write_i16(-1);
+        }
+        assert_eq!(calls, ["write([97, 98])", "write_i16(-1)"]);
+    }
+
+    #[test]
+    fn sees_u8_stream_signal_inside() {
+        const PF: ProtocolFlags = crate::new::u8_stream::signal_first::u64();
+        let mut calls = Vec::<String>::new();
+        {
+            let mut hasher = SignalledInjectionHasher::<_, PF>::from(InspectHasher::new(
+                DefaultHasher::new(),
+                |call: &str| calls.push(call.to_string()),
+            ));
+            crate::inject::<_, PF>(&mut hasher, 42);
+            assert_eq!(hasher.finish(), 42);
+        }
+        // The signal, but not the hash that follows it.
+        let signal: Vec<_> = crate::signal::U8_STREAM_SIGNAL_HASH
+            .iter()
+            .map(|byte| format!("write_u8({byte})"))
+            .collect();
+        assert_eq!(calls, signal);
+    }
+}
@@ -0,0 +1,534 @@
+use core::cell::OnceCell;
+use core::fmt::{self, Debug, Formatter};
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::mem;
+
+use crate::ProtocolFlags;
+use crate::flags;
+
+/// A replacement for `Option<T>` as a key, where `T`'s [Hash] implementation injects a hash (with
+/// [crate::inject]).
+///
+/// Do NOT use a raw `Option<T>` for such keys: Its [Hash] implementation writes the discriminant
+/// before `T` writes anything. That is ordinary data, so the injection that follows it breaks
+/// submit-first protocols. (Signal-first protocols combine them, as for [PartialInject], but that
+/// is more work than needed.)
+///
+/// Instead, [Hash] for [OptionKey]
+/// - writes a discriminant (as ordinary data) for [None], but
+/// - hashes ONLY the inner value for [Some] - without a discriminant, since the injected hash
+///   already differs from the ordinary hash of [None].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+pub struct OptionKey<T>(pub Option<T>);
+
+impl<T: Hash> Hash for OptionKey<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match &self.0 {
+            None => mem::discriminant(&self.0).hash(state),
+            Some(inner) => inner.hash(state),
+        }
+    }
+}
+
+impl<T> From<Option<T>> for OptionKey<T> {
+    fn from(option: Option<T>) -> Self {
+        Self(option)
+    }
+}
+
+/// A compact key that stores only a hash (and injects it, as per protocol `PF`). Use it for
+/// "have I seen this hash?" membership tests (for example, a lightweight dedup set), without
+/// storing the hashed values themselves.
+///
+/// Equality is equality of the hashes.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct HashOnly<const PF: ProtocolFlags> {
+    pub hash: u64,
+}
+
+impl<const PF: ProtocolFlags> HashOnly<PF> {
+    pub const fn new(hash: u64) -> Self {
+        Self { hash }
+    }
+}
+
+impl<const PF: ProtocolFlags> Hash for HashOnly<PF> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        crate::inject::<_, PF>(state, self.hash);
+    }
+}
+
+/// A key made of several [HashOnly]s (a slice, an array, or, with `std` feature, a `Vec` of them),
+/// which injects ONE hash: a combination of their hashes (see [Slice::combined_hash]).
+///
+/// Do NOT use a raw slice (or an array or a `Vec`) of keys that inject hashes as a key: Its [Hash]
+/// implementation writes a length prefix (as ordinary data), and then it lets each element inject
+/// its hash. That breaks the signalling protocol.
+///
+/// Equality is equality of the elements, in order.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+pub struct Slice<T: ?Sized>(pub T);
+
+/// Combine the hashes of all `elements`, in order, into one.
+///
+/// Each step rotates the hash accumulated so far, XORs it with the element's hash and multiplies
+/// it (wrapping) by 2^64 divided by the golden ratio. The accumulator starts as the number of
+/// elements. So (most likely) the result differs for a different order of elements, and for slices
+/// of different lengths.
+const fn combine<const PF: ProtocolFlags>(elements: &[HashOnly<PF>]) -> u64 {
+    let mut hash = elements.len() as u64;
+    let mut i = 0;
+    while i < elements.len() {
+        hash = (hash.rotate_left(5) ^ elements[i].hash).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        i += 1;
+    }
+    hash
+}
+
+impl<const PF: ProtocolFlags> Slice<[HashOnly<PF>]> {
+    /// The hash that this injects: a combination of the hashes of all elements, in order.
+    /// Deterministic. Use it where you need the hash directly (for example, with
+    /// `insert_by_hash`, if enabled by `raw-entry` feature).
+    #[must_use]
+    pub const fn combined_hash(&self) -> u64 {
+        combine(&self.0)
+    }
+}
+impl<const PF: ProtocolFlags, const N: usize> Slice<[HashOnly<PF>; N]> {
+    /// See [Slice::combined_hash] for slices.
+    #[must_use]
+    pub const fn combined_hash(&self) -> u64 {
+        combine(&self.0)
+    }
+}
+#[cfg(feature = "std")]
+impl<const PF: ProtocolFlags> Slice<std::vec::Vec<HashOnly<PF>>> {
+    /// See [Slice::combined_hash] for slices.
+    #[must_use]
+    pub fn combined_hash(&self) -> u64 {
+        combine(&self.0)
+    }
+}
+
+impl<const PF: ProtocolFlags> Hash for Slice<[HashOnly<PF>]> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        crate::inject::<_, PF>(state, self.combined_hash());
+    }
+}
+impl<const PF: ProtocolFlags, const N: usize> Hash for Slice<[HashOnly<PF>; N]> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        crate::inject::<_, PF>(state, self.combined_hash());
+    }
+}
+#[cfg(feature = "std")]
+impl<const PF: ProtocolFlags> Hash for Slice<std::vec::Vec<HashOnly<PF>>> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        crate::inject::<_, PF>(state, self.combined_hash());
+    }
+}
+
+/// A composite key with an ordinary component (hashed as usual) and a pre-computed hash (injected,
+/// as per protocol `PF`).
+///
+/// Its [Hash] implementation writes `ordinary` first, and then injects `hash` - signalling twice in
+/// a row. Once [crate::SignalledInjectionHasher] receives such a double signal after ordinary data,
+/// it does NOT discard that data. Instead, it combines the injected hash with what its underlying
+/// [Hasher] returns for the ordinary data so far (as [crate::tag_hash] does). So keys that differ
+/// in either component (most likely) have different hashes. (A plain [crate::inject] after
+/// ordinary data is a misuse, which `chk` reports.)
+///
+/// Supported only by signal-first protocols (other than `trusted`): With submit-first protocols,
+/// the hash can't be told apart from ordinary data that precedes it. Using any other protocol
+/// fails to compile.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+pub struct PartialInject<T, const PF: ProtocolFlags> {
+    pub ordinary: T,
+    pub hash: u64,
+}
+
+impl<T, const PF: ProtocolFlags> PartialInject<T, PF> {
+    pub const fn new(ordinary: T, hash: u64) -> Self {
+        Self { ordinary, hash }
+    }
+}
+
+impl<T: Hash, const PF: ProtocolFlags> Hash for PartialInject<T, PF> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        const {
+            assert!(
+                flags::is_signal_first(PF),
+                "PartialInject requires a signal-first protocol."
+            )
+        };
+        self.ordinary.hash(state);
+        crate::signal::inject_combined::<_, PF>(state, self.hash);
+    }
+}
+
+/// A key whose hash is expensive, and maybe never needed: It stores `value` and a reference to a
+/// [BuildHasher] `B`. It computes the hash of `value` with `B` only when first needed (by [Hash],
+/// or by [LazyHash::cached_hash]), caches it, and it injects the cached hash (as per protocol
+/// `PF`).
+///
+/// Equality is equality of the values. Keys that are equal must use the same `B` (for example, a
+/// reference to one shared `RandomState`), or their hashes differ.
+///
+/// The cache is a [OnceCell], so [LazyHash] is NOT [Sync]. (It is [Send] if `T` is [Send] and `B`
+/// is [Sync].)
+pub struct LazyHash<'b, T, B, const PF: ProtocolFlags> {
+    value: T,
+    build: &'b B,
+    hash: OnceCell<u64>,
+}
+
+impl<'b, T, B, const PF: ProtocolFlags> LazyHash<'b, T, B, PF> {
+    pub const fn new(value: T, build: &'b B) -> Self {
+        Self {
+            value,
+            build,
+            hash: OnceCell::new(),
+        }
+    }
+
+    pub const fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Whether the hash has been computed already.
+    #[must_use]
+    pub fn is_hashed(&self) -> bool {
+        self.hash.get().is_some()
+    }
+}
+
+impl<T: Hash, B: BuildHasher, const PF: ProtocolFlags> LazyHash<'_, T, B, PF> {
+    /// The hash of `value` (with `B`). Computed on the first call (or the first [Hash::hash]) only.
+    #[must_use]
+    pub fn cached_hash(&self) -> u64 {
+        *self.hash.get_or_init(|| self.build.hash_one(&self.value))
+    }
+}
+
+impl<T: Hash, B: BuildHasher, const PF: ProtocolFlags> Hash for LazyHash<'_, T, B, PF> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        crate::inject::<_, PF>(state, self.cached_hash());
+    }
+}
+
+impl<T: PartialEq, B, const PF: ProtocolFlags> PartialEq for LazyHash<'_, T, B, PF> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+impl<T: Eq, B, const PF: ProtocolFlags> Eq for LazyHash<'_, T, B, PF> {}
+
+impl<T: Debug, B, const PF: ProtocolFlags> Debug for LazyHash<'_, T, B, PF> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyHash")
+            .field("value", &self.value)
+            .field("hash", &self.hash.get())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "hpe"))]
+mod tests {
+    use super::*;
+    use crate::SignalledInjectionBuildHasher;
+    use core::hash::BuildHasher;
+    use std::collections::HashSet;
+    use std::hash::RandomState;
+    #[cfg(feature = "std")]
+    use std::vec;
+
+    #[derive(PartialEq, Eq, Debug)]
+    struct Injecting<const PF: ProtocolFlags>(u64);
+    impl<const PF: ProtocolFlags> Hash for Injecting<PF> {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            crate::inject::<_, PF>(state, self.0);
+        }
+    }
+
+    fn some_and_none_in_a_set<const PF: ProtocolFlags>() {
+        let mut set = HashSet::with_hasher(SignalledInjectionBuildHasher::<_, _, PF>::new(
+            RandomState::new(),
+        ));
+        assert!(set.insert(OptionKey(None)));
+        assert!(set.insert(OptionKey(Some(Injecting::<PF>(1)))));
+        assert!(set.insert(OptionKey(Some(Injecting::<PF>(2)))));
+        assert!(!set.insert(OptionKey(None)));
+        assert!(!set.insert(OptionKey(Some(Injecting::<PF>(1)))));
+
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&OptionKey(None)));
+        assert!(set.contains(&OptionKey(Some(Injecting::<PF>(2)))));
+        assert!(!set.contains(&OptionKey(Some(Injecting::<PF>(3)))));
+    }
+
+    #[test]
+    fn some_and_none_in_a_set_signal_first() {
+        some_and_none_in_a_set::<{ crate::new::len::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn some_and_none_in_a_set_submit_first() {
+        some_and_none_in_a_set::<{ crate::new::len::submit_first::u64() }>();
+    }
+
+    fn hash_only_in_a_set<const PF: ProtocolFlags>() {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut set = HashSet::with_hasher(build);
+        assert!(set.insert(HashOnly::<PF>::new(1)));
+        assert!(set.insert(HashOnly::<PF>::new(u64::MAX)));
+        assert!(!set.insert(HashOnly::<PF>::new(1)));
+
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&HashOnly::<PF>::new(u64::MAX)));
+        assert!(!set.contains(&HashOnly::<PF>::new(2)));
+        assert_eq!(set.hasher().hash_one(HashOnly::<PF>::new(2)), 2);
+    }
+
+    #[test]
+    fn hash_only_in_a_set_signal_first() {
+        hash_only_in_a_set::<{ crate::new::len::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn hash_only_in_a_set_submit_first() {
+        hash_only_in_a_set::<{ crate::new::len::submit_first::u64() }>();
+    }
+
+    /// `k1 == k2` must imply `hash(k1) == hash(k2)` (the contract of [Hash]).
+    fn eq_implies_equal_hashes<const PF: ProtocolFlags>() {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let option_key = |hash: Option<u64>| OptionKey(hash.map(Injecting::<PF>));
+        for hash in [None, Some(0), Some(1), Some(u64::MAX)] {
+            let (k1, k2) = (option_key(hash), option_key(hash));
+            assert_eq!(k1, k2);
+            assert_eq!(build.hash_one(&k1), build.hash_one(&k2));
+        }
+        for hash in [0, 1, u64::MAX] {
+            let (k1, k2) = (HashOnly::<PF>::new(hash), HashOnly::<PF>::new(hash));
+            assert_eq!(k1, k2);
+            assert_eq!(build.hash_one(k1), build.hash_one(k2));
+        }
+    }
+
+    #[test]
+    fn eq_implies_equal_hashes_signal_first() {
+        eq_implies_equal_hashes::<{ crate::new::len::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn eq_implies_equal_hashes_submit_first() {
+        eq_implies_equal_hashes::<{ crate::new::len::submit_first::u64() }>();
+    }
+    fn partial_inject_in_a_set<const PF: ProtocolFlags>() {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut set = HashSet::with_hasher(build);
+        assert!(set.insert(PartialInject::<_, PF>::new("a", 1)));
+        assert!(set.insert(PartialInject::<_, PF>::new("a", 2)));
+        assert!(set.insert(PartialInject::<_, PF>::new("b", 1)));
+        assert!(!set.insert(PartialInject::<_, PF>::new("a", 1)));
+        assert_eq!(set.len(), 3);
+
+        let hash = |key: PartialInject<&str, PF>| set.hasher().hash_one(key);
+        assert_eq!(
+            hash(PartialInject::new("a", 1)),
+            hash(PartialInject::new("a", 1))
+        );
+        assert_ne!(
+            hash(PartialInject::new("a", 1)),
+            hash(PartialInject::new("a", 2))
+        );
+        assert_ne!(
+            hash(PartialInject::new("a", 1)),
+            hash(PartialInject::new("b", 1))
+        );
+        // Neither the injected hash alone, nor the ordinary hash alone.
+        assert_ne!(hash(PartialInject::new("a", 1)), 1);
+        assert_ne!(hash(PartialInject::new("a", 1)), set.hasher().hash_one("a"));
+    }
+
+    #[test]
+    fn partial_inject_in_a_set_len() {
+        partial_inject_in_a_set::<{ crate::new::len::signal_first::u64() }>();
+        partial_inject_in_a_set::<{ crate::new::len::signal_first::i128() }>();
+    }
+
+    #[test]
+    fn partial_inject_in_a_set_u8_stream() {
+        partial_inject_in_a_set::<{ crate::new::u8_stream::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn partial_inject_in_a_set_cookie() {
+        partial_inject_in_a_set::<{ crate::new::usize::signal_first::u64() }>();
+        partial_inject_in_a_set::<{ crate::new::len_cookie::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn partial_inject_combines_with_the_underlying_hasher() {
+        const PF: ProtocolFlags = crate::new::len::signal_first::u64();
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        assert_eq!(
+            build.hash_one(PartialInject::<_, PF>::new("a", 7)),
+            crate::tag_hash(7, build.hash_one("a"))
+        );
+    }
+
+    fn slices_in_a_set<const PF: ProtocolFlags>() {
+        let (a, b) = (HashOnly::<PF>::new(1), HashOnly::<PF>::new(2));
+        let build = || SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+
+        let mut set = HashSet::with_hasher(build());
+        assert!(set.insert(Slice([a, b])));
+        assert!(set.insert(Slice([b, a])));
+        assert!(!set.insert(Slice([a, b])));
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&Slice([b, a])));
+        assert!(!set.contains(&Slice([b, b])));
+
+        // The same hash, whether it's an array or a slice.
+        let slice: &Slice<[HashOnly<PF>]> = &Slice([a, b]);
+        assert_eq!(build().hash_one(slice), slice.combined_hash());
+        assert_eq!(build().hash_one(Slice([a, b])), slice.combined_hash());
+        assert_ne!(Slice([a, b]).combined_hash(), Slice([b, a]).combined_hash());
+        assert_ne!(Slice([a]).combined_hash(), Slice([a, a]).combined_hash());
+        assert_ne!(
+            Slice::<[HashOnly<PF>; 0]>([]).combined_hash(),
+            Slice([HashOnly::<PF>::new(0)]).combined_hash()
+        );
+    }
+
+    #[test]
+    fn slices_in_a_set_signal_first() {
+        slices_in_a_set::<{ crate::new::len::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn slices_in_a_set_submit_first() {
+        slices_in_a_set::<{ crate::new::len::submit_first::u64() }>();
+    }
+
+    #[cfg(feature = "std")]
+    fn vecs_in_a_set<const PF: ProtocolFlags>() {
+        let (a, b) = (HashOnly::<PF>::new(1), HashOnly::<PF>::new(2));
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut set = HashSet::with_hasher(build);
+        assert!(set.insert(Slice(vec![a, b])));
+        assert!(set.insert(Slice(vec![b, a])));
+        assert!(set.insert(Slice(vec![a])));
+        assert!(set.insert(Slice(vec![])));
+        assert!(!set.insert(Slice(vec![a, b])));
+        assert_eq!(set.len(), 4);
+        assert!(set.contains(&Slice(vec![b, a])));
+        assert!(!set.contains(&Slice(vec![b])));
+        assert_eq!(
+            set.hasher().hash_one(Slice(vec![a, b])),
+            Slice([a, b]).combined_hash()
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn vecs_in_a_set_signal_first() {
+        vecs_in_a_set::<{ crate::new::len::signal_first::u64() }>();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn vecs_in_a_set_submit_first() {
+        vecs_in_a_set::<{ crate::new::len::submit_first::u64() }>();
+    }
+}
+
+#[cfg(test)]
+mod tests_lazy_hash {
+    use super::*;
+    use crate::SignalledInjectionBuildHasher;
+    use std::collections::HashSet;
+    use std::hash::RandomState;
+
+    /// Counts how many times it was hashed.
+    struct Counted<'c> {
+        value: u64,
+        count: &'c core::cell::Cell<usize>,
+    }
+    impl Hash for Counted<'_> {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.count.set(self.count.get() + 1);
+            self.value.hash(state);
+        }
+    }
+    impl PartialEq for Counted<'_> {
+        fn eq(&self, other: &Self) -> bool {
+            self.value == other.value
+        }
+    }
+    impl Eq for Counted<'_> {}
+
+    // Neither the cache nor the count affect the hash or equality.
+    #[allow(clippy::mutable_key_type)]
+    fn lazy_hash_in_a_set<const PF: ProtocolFlags>() {
+        let inner = RandomState::new();
+        let count = core::cell::Cell::new(0);
+        let key = |value| {
+            LazyHash::<_, _, PF>::new(
+                Counted {
+                    value,
+                    count: &count,
+                },
+                &inner,
+            )
+        };
+
+        let mut set = HashSet::with_hasher(SignalledInjectionBuildHasher::<_, _, PF>::new(
+            RandomState::new(),
+        ));
+        let one = key(1);
+        assert!(!one.is_hashed());
+        assert_eq!(count.get(), 0);
+
+        assert!(set.insert(one));
+        assert_eq!(count.get(), 1);
+        assert!(set.insert(key(2)));
+        assert_eq!(count.get(), 2);
+        // A fresh (equal) key computes its own hash, once.
+        assert!(set.contains(&key(1)));
+        assert_eq!(count.get(), 3);
+
+        let three = key(3);
+        let hash = three.cached_hash();
+        assert!(three.is_hashed());
+        assert_eq!(hash, inner.hash_one(3u64));
+        assert_eq!(three.cached_hash(), hash);
+        assert!(set.insert(three));
+        assert_eq!(count.get(), 4);
+        // Growing the set rehashes the stored keys, from their caches.
+        for value in 4..100 {
+            assert!(set.insert(key(value)));
+        }
+        assert_eq!(count.get(), 100);
+        assert_eq!(set.len(), 99);
+    }
+
+    #[test]
+    fn u8_stream() {
+        lazy_hash_in_a_set::<{ crate::new::u8_stream::signal_first::u64() }>();
+        lazy_hash_in_a_set::<{ crate::new::u8_stream::submit_first::u64() }>();
+    }
+
+    #[test]
+    fn usize() {
+        lazy_hash_in_a_set::<{ crate::new::usize::signal_first::u64() }>();
+        lazy_hash_in_a_set::<{ crate::new::usize::submit_first::u64() }>();
+    }
+
+    #[cfg(feature = "hpe")]
+    #[test]
+    fn len() {
+        lazy_hash_in_a_set::<{ crate::new::len::signal_first::u64() }>();
+        lazy_hash_in_a_set::<{ crate::new::len::submit_first::u64() }>();
+    }
+}
@@ -1,40 +1,58 @@
 #![doc = include_str!("../README.md")]
-#![cfg_attr(not(any(feature = "mx", test)), no_std)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![cfg_attr(not(any(feature = "mx", feature = "ndd")), forbid(unsafe_code))]
 #![cfg_attr(feature = "mx", feature(mutex_data_ptr))] // https://github.com/rust-lang/rust/issues/140368
 #![cfg_attr(feature = "hpe", feature(hasher_prefixfree_extras))] //  https://github.com/rust-lang/rust/issues/96762
 #![cfg_attr(feature = "flags", feature(adt_const_params))]
-// https://github.com/rust-lang/rust/issues/95174
-//#![cfg_attr(feature = "chk-details", feature(format_args))]
-#![cfg_attr(feature = "chk-details", allow(internal_features))]
-#![cfg_attr(
-    feature = "chk-details",
-    // No tracking issues (as of mid 2025). Only
-    // https://doc.rust-lang.org/nightly/unstable-book/library-features/const-format-args.html
-    // https://doc.rust-lang.org/nightly/unstable-book/library-features/fmt-internals.html
-    // https://doc.rust-lang.org/nightly/unstable-book/library-features/panic-internals.html
-    feature(const_format_args, fmt_internals, panic_internals)
-)]
-// - const_index https://github.com/rust-lang/rust/issues/143775
-// - const_trait_impl https://github.com/rust-lang/rust/issues/143874
-//
-// @TODO const_index:
-//
-//#![cfg_attr(feature = "chk-details", feature(const_index, const_trait_impl))]
-#![cfg_attr(feature = "chk-details", feature(const_trait_impl))]
 #![forbid(unused_must_use)]
 
 #[cfg(all(feature = "mx", feature = "ndd"))]
 compile_error!("Do not use both 'mx' and 'ndd' cargo feature.");
 
-pub use flags::{ProtocolFlags, new};
-pub use hasher::{SignalledInjectionBuildHasher, SignalledInjectionHasher};
-pub use signal::inject;
+#[cfg(feature = "std")]
+pub use collections::{InjectingHashMap, InjectingHashSet};
+pub use combining::{CombiningInjector, combine_hash};
+pub use features::active_features;
+pub use flags::{
+    ALL_PROTOCOLS, HashVia, InvalidFlagsBits, ProtocolFlags, SignalVia, flags_from_bits,
+    flags_to_bits, hash_via_bytes, is_valid, new, try_hash_via, try_signal_via,
+};
+pub use fold::{Fold128, fold128};
+pub use hasher::{FinishError, SignalledInjectionBuildHasher, SignalledInjectionHasher};
+#[cfg(feature = "std")]
+pub use inspect::InspectHasher;
+pub use keys::{HashOnly, LazyHash, OptionKey, PartialInject, Slice};
+#[cfg(feature = "raw-entry")]
+pub use raw_entry::{Occupied, insert_by_hash, insert_unique_by_hash};
+pub use signal::{inject, inject_dyn, inject_nonzero, inject_tagged, inject_with_flags, tag_hash};
+pub use verify::VerifyOnceBuildHasher;
 
+#[cfg(feature = "std")]
+mod collections;
+mod combining;
+#[cfg(feature = "std")]
+pub mod examples;
+mod features;
 mod flags;
+mod fold;
+#[cfg(feature = "fxhash")]
+pub mod fx;
 mod hasher;
+#[cfg(feature = "std")]
+mod inspect;
+mod keys;
+mod macros;
+pub mod prelude;
+#[cfg(feature = "raw-entry")]
+mod raw_entry;
+#[cfg(feature = "seahash")]
+pub mod sea;
 mod signal;
+pub mod signals;
 mod state;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod verify;
 
 #[cfg(test)]
 mod tests {
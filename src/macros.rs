@@ -0,0 +1,127 @@
+/// For test suites: Wrap the given [core::hash::BuildHasher] in
+/// [crate::SignalledInjectionBuildHasher] for the given protocol (a `const` expression of type
+/// [crate::ProtocolFlags]), build a [core::hash::Hasher], [crate::inject] the given hash, and
+/// assert that [core::hash::Hasher::finish] returns it.
+///
+/// ```
+/// use std::hash::RandomState;
+///
+/// hash_injector::verify_protocol_roundtrip!(
+///     RandomState::new(),
+///     hash_injector::new::trusted::u64(),
+///     0x0123_4567_89AB_CDEF
+/// );
+/// ```
+#[macro_export]
+macro_rules! verify_protocol_roundtrip {
+    ($build_hasher:expr, $protocol_flags:expr, $hash:expr) => {{
+        const PF: $crate::ProtocolFlags = $protocol_flags;
+        let build = $crate::SignalledInjectionBuildHasher::<_, _, PF>::new($build_hasher);
+        let mut hasher = ::core::hash::BuildHasher::build_hasher(&build);
+        let hash: u64 = $hash;
+        $crate::inject::<_, PF>(&mut hasher, hash);
+        assert_eq!(::core::hash::Hasher::finish(&hasher), hash);
+    }};
+}
+
+/// The items of a module with [crate::SignalledInjectionBuildHasher] preconfigured with `$hasher`
+/// (module [crate::fx] or [crate::sea]): type alias `$build`, function `build_hasher`, and tests.
+#[cfg(any(feature = "fxhash", feature = "seahash"))]
+macro_rules! preconfigured {
+    ($hasher:ident, $build:ident) => {
+        pub type $build<const PF: $crate::ProtocolFlags> = $crate::SignalledInjectionBuildHasher<
+            $hasher,
+            ::core::hash::BuildHasherDefault<$hasher>,
+            PF,
+        >;
+
+        #[doc = concat!("Return a ready [", stringify!($build), "] for protocol `PF`.")]
+        ///
+        #[doc = concat!("[", stringify!($hasher), "] is NOT randomly seeded. Do NOT use it for")]
+        /// (ordinary) keys controlled by third parties (Hash DoS).
+        pub fn build_hasher<const PF: $crate::ProtocolFlags>() -> $build<PF> {
+            $crate::SignalledInjectionBuildHasher::new(::core::hash::BuildHasherDefault::default())
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use core::hash::{BuildHasherDefault, Hash};
+
+            fn round_trip<const PF: $crate::ProtocolFlags>() {
+                assert_eq!(build_hasher::<PF>().hash_one_injected(42), 42);
+                $crate::testing::assert_round_trip::<_, PF>(
+                    BuildHasherDefault::<$hasher>::default(),
+                    &[0, 1, 0x0123_4567_89AB_CDEF, u64::MAX],
+                );
+                // Ordinary keys pass through (except for `trusted` protocols, if the key is of the
+                // same type as the hash).
+                $crate::testing::assert_passthrough::<_, PF>(
+                    BuildHasherDefault::<$hasher>::default(),
+                    &[&|mut h| 0x42u32.hash(&mut h)],
+                );
+            }
+
+            #[test]
+            fn round_trip_trusted() {
+                round_trip::<{ $crate::new::trusted::u64() }>();
+            }
+
+            #[test]
+            fn round_trip_u8_stream() {
+                round_trip::<{ $crate::new::u8_stream::signal_first::u64() }>();
+                round_trip::<{ $crate::new::u8_stream::submit_first::i128() }>();
+            }
+
+            #[cfg(feature = "hpe")]
+            #[test]
+            fn round_trip_len() {
+                round_trip::<{ $crate::new::len::signal_first::u64() }>();
+                round_trip::<{ $crate::new::len::submit_first::u128() }>();
+            }
+        }
+    };
+}
+#[cfg(any(feature = "fxhash", feature = "seahash"))]
+pub(crate) use preconfigured;
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    const HASH: u64 = 0x0123_4567_89AB_CDEF;
+
+    #[test]
+    fn trusted() {
+        verify_protocol_roundtrip!(RandomState::new(), crate::new::trusted::u64(), HASH);
+        verify_protocol_roundtrip!(RandomState::new(), crate::new::trusted::i64(), HASH);
+        verify_protocol_roundtrip!(RandomState::new(), crate::new::trusted::u128(), HASH);
+        verify_protocol_roundtrip!(RandomState::new(), crate::new::trusted::i128(), HASH);
+    }
+
+    #[cfg(feature = "hpe")]
+    #[test]
+    fn len() {
+        use std::hash::{BuildHasherDefault, DefaultHasher};
+        let build = BuildHasherDefault::<DefaultHasher>::default();
+        verify_protocol_roundtrip!(build.clone(), crate::new::len::signal_first::u64(), HASH);
+        verify_protocol_roundtrip!(build.clone(), crate::new::len::signal_first::i128(), HASH);
+        verify_protocol_roundtrip!(build.clone(), crate::new::len::submit_first::u64(), HASH);
+        verify_protocol_roundtrip!(build, crate::new::len::submit_first::i128(), HASH);
+    }
+
+    #[cfg(feature = "mx")]
+    #[test]
+    fn u8s() {
+        verify_protocol_roundtrip!(
+            RandomState::new(),
+            crate::new::u8s::signal_first::u64(),
+            HASH
+        );
+        verify_protocol_roundtrip!(
+            RandomState::new(),
+            crate::new::u8s::submit_first::u64(),
+            HASH
+        );
+    }
+}
@@ -0,0 +1,25 @@
+//! Re-exports of the most commonly used items.
+//!
+//! ```
+//! use hash_injector::prelude::*;
+//!
+//! # #[cfg(feature = "hpe")]
+//! # {
+//! const PF: ProtocolFlags = len::signal_first::u64();
+//!
+//! let build = SignalledInjectionBuildHasher::<_, _, PF>::new(std::hash::RandomState::new());
+//! let mut hasher = build.build_hasher();
+//! inject::<_, PF>(&mut hasher, 42);
+//! assert_eq!(hasher.finish(), 42);
+//! # }
+//! ```
+
+pub use core::hash::{BuildHasher, Hasher};
+
+pub use crate::{
+    FinishError, HashOnly, OptionKey, ProtocolFlags, SignalledInjectionBuildHasher,
+    SignalledInjectionHasher, inject, inject_dyn, new,
+};
+
+#[cfg(feature = "hpe")]
+pub use crate::new::len;
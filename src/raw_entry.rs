@@ -0,0 +1,135 @@
+use core::hash::{BuildHasher, Hash};
+use hashbrown::HashMap;
+
+/// Insert `key` and `value` into `map`, placing the entry by the given (precomputed) `hash` - with
+/// neither calling [Hash] on `key`, nor comparing keys. Return references to the inserted key and
+/// value.
+///
+/// The caller is responsible that
+/// - `key` is not in `map` yet, and
+/// - `hash` is what `map`'s [BuildHasher] returns for `key`. That is the case if `key` injects
+///   `hash` (see [crate::inject]) and `map` uses [crate::SignalledInjectionBuildHasher]. The map
+///   does hash `key` when it grows (rehashes), and when looking it up with [HashMap::get] etc.
+///
+/// Otherwise the map may contain duplicates, or may not find the entry.
+pub fn insert_by_hash<K: Hash, V, B: BuildHasher>(
+    map: &mut HashMap<K, V, B>,
+    hash: u64,
+    key: K,
+    value: V,
+) -> (&mut K, &mut V) {
+    match map.raw_entry_mut().from_hash(hash, |_| false) {
+        hashbrown::hash_map::RawEntryMut::Vacant(vacant) => {
+            vacant.insert_hashed_nocheck(hash, key, value)
+        }
+        hashbrown::hash_map::RawEntryMut::Occupied(_) => unreachable!(),
+    }
+}
+
+/// The `key` and `value` that [insert_unique_by_hash] did not insert, because `map` already
+/// contained an equal key.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Occupied<K, V> {
+    pub key: K,
+    pub value: V,
+}
+
+/// Like [insert_by_hash], but insert `key` and `value` only if `map` has no entry with the same
+/// `hash` AND a key equal to `key`. Otherwise return them back in [Occupied].
+///
+/// Injected hashes may collide even though their keys differ, hence this still compares keys (but
+/// only those of entries with the same `hash`). It doesn't call [Hash] on `key`. The caller is
+/// responsible that `hash` is what `map`'s [BuildHasher] returns for `key` - see [insert_by_hash].
+pub fn insert_unique_by_hash<K: Hash + Eq, V, B: BuildHasher>(
+    map: &mut HashMap<K, V, B>,
+    hash: u64,
+    key: K,
+    value: V,
+) -> Result<(&mut K, &mut V), Occupied<K, V>> {
+    match map.raw_entry_mut().from_hash(hash, |k| *k == key) {
+        hashbrown::hash_map::RawEntryMut::Vacant(vacant) => {
+            Ok(vacant.insert_hashed_nocheck(hash, key, value))
+        }
+        hashbrown::hash_map::RawEntryMut::Occupied(_) => Err(Occupied { key, value }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ProtocolFlags, SignalledInjectionBuildHasher};
+    use core::hash::Hasher;
+    use std::hash::RandomState;
+
+    const PF: ProtocolFlags = crate::new::trusted::u64();
+
+    #[derive(PartialEq, Eq, Debug)]
+    struct Key {
+        id: u32,
+        hash: u64,
+    }
+    impl Hash for Key {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            crate::inject::<_, PF>(state, self.hash);
+        }
+    }
+    fn key(id: u32) -> Key {
+        Key {
+            id,
+            hash: (id as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15),
+        }
+    }
+
+    #[test]
+    fn insert_by_hash_then_get() {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut map = HashMap::with_hasher(build);
+        // Enough entries for the map to grow (and rehash) several times.
+        for id in 0..100 {
+            let Key { id, hash } = key(id);
+            let (_, value) = insert_by_hash(&mut map, hash, Key { id, hash }, id * 10);
+            assert_eq!(*value, id * 10);
+        }
+        assert_eq!(map.len(), 100);
+        for id in 0..100 {
+            assert_eq!(map.get(&key(id)), Some(&(id * 10)));
+
+            let Key { hash, .. } = key(id);
+            let found = map.raw_entry().from_hash(hash, |k| k.id == id);
+            assert_eq!(found, Some((&key(id), &(id * 10))));
+        }
+        assert_eq!(map.get(&key(100)), None);
+    }
+
+    #[test]
+    fn insert_unique_by_hash_collide_different_key() {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut map = HashMap::with_hasher(build);
+        let hash = key(1).hash;
+        assert!(insert_unique_by_hash(&mut map, hash, Key { id: 1, hash }, 10).is_ok());
+        // Same hash, but a different key: Both entries are kept.
+        assert!(insert_unique_by_hash(&mut map, hash, Key { id: 2, hash }, 20).is_ok());
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&Key { id: 1, hash }), Some(&10));
+        assert_eq!(map.get(&Key { id: 2, hash }), Some(&20));
+    }
+
+    #[test]
+    fn insert_unique_by_hash_collide_same_key() {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut map = HashMap::with_hasher(build);
+        let hash = key(1).hash;
+        assert!(insert_unique_by_hash(&mut map, hash, key(1), 10).is_ok());
+        assert_eq!(
+            insert_unique_by_hash(&mut map, hash, key(1), 20),
+            Err(Occupied {
+                key: key(1),
+                value: 20
+            })
+        );
+
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get(&key(1)), Some(&10));
+    }
+}
@@ -0,0 +1,11 @@
+//! [crate::SignalledInjectionBuildHasher] preconfigured with [SeaHasher].
+//!
+//! Any protocol works: [crate::SignalledInjectionHasher] recognizes signals itself, so it doesn't
+//! matter which `Hasher::write_xxx` methods [SeaHasher] specializes.
+//!
+//! Some signals and hashes reach [SeaHasher], too - for example, bytes of `u8_stream` signals, and
+//! hashes submitted by submit-first protocols. But then its result is not used.
+
+use seahash::SeaHasher;
+
+crate::macros::preconfigured!(SeaHasher, SeaBuildHasher);
@@ -1,4 +1,5 @@
 use core::hash::Hasher;
+use core::num::NonZeroU64;
 //use core::slice;
 
 #[cfg(feature = "mx")]
@@ -16,62 +17,140 @@ use flags::{Flow, HashVia, ProtocolFlags, SignalVia};
 #[cfg(feature = "hpe")]
 /// A fictitious slice length, which represents a signal that we either just handed an injected
 /// hash, or we are about to hand it - depending on whether we signal first, or submit first.
-pub const LEN_SIGNAL_HASH: usize = usize::MAX;
+///
+/// It depends on protocol `flags`: `usize::MAX - 0xFF` plus [crate::flags_to_bits] of `flags`. So
+/// [crate::SignalledInjectionHasher]s of different `len` protocols that share a pipeline treat each
+/// other's signals as ordinary data.
+#[must_use]
+pub const fn len_signal_hash(flags: ProtocolFlags) -> usize {
+    usize::MAX - 0xFF + flags::flags_to_bits(flags) as usize
+}
+
+// Each `len` (and `len_carrier`) protocol has its own signal, and none of them is a check-flow
+// sentinel.
+#[cfg(feature = "hpe")]
+const _: () = {
+    let mut i = 0;
+    while i < flags::ALL_PROTOCOLS.len() {
+        let pf = flags::ALL_PROTOCOLS[i];
+        if flags::is_signal_via_len(pf) || flags::is_signal_via_len_carrier(pf) {
+            let signal = len_signal_hash(pf);
+            assert!(signal < LEN_SIGNAL_CHECK_FLOW_IS_SUBMIT_FIRST);
+            assert!(signal < LEN_SIGNAL_CHECK_FLOW_IS_SIGNAL_FIRST);
+            let mut j = 0;
+            while j < i {
+                let other = flags::ALL_PROTOCOLS[j];
+                if flags::is_signal_via_len(other) || flags::is_signal_via_len_carrier(other) {
+                    assert!(signal != len_signal_hash(other));
+                }
+                j += 1;
+            }
+        }
+        i += 1;
+    }
+};
+
+// The `len_carrier` protocol carries each 32-bit half of the hash in a `usize`.
+#[cfg(feature = "hpe")]
+const _: () = assert!(usize::BITS >= 32);
 
-#[cfg(all(feature = "hpe", feature = "chk-flow"))]
+#[cfg(feature = "hpe")]
+/// A cookie, written with [`Hasher::write_u64`] right after a zero length (written with
+/// [`Hasher::write_length_prefix`]). The pair represents a signal (like [len_signal_hash], but
+/// without reserving any length). A zero length followed by anything else is ordinary data.
+pub const LEN_COOKIE_SIGNAL_HASH: u64 = 0x5D1C_A7E5_0C00_C1E5;
+
+/// A value written with [`Hasher::write_usize`], which represents a signal ONLY if
+/// [USIZE_COOKIE_SIGNAL_HASH] (written with [`Hasher::write_u64`]) follows it right away. Like the
+/// pair for `len_cookie` protocols, but available without `hpe` feature.
+///
+/// Many [core::hash::Hash] implementations write collection lengths with `write_usize`, so
+/// `usize::MAX` alone is ordinary data.
+pub const USIZE_SIGNAL_HASH: usize = usize::MAX;
+
+/// A cookie, written with [`Hasher::write_u64`] right after [USIZE_SIGNAL_HASH] (written with
+/// [`Hasher::write_usize`]). See [USIZE_SIGNAL_HASH].
+pub const USIZE_COOKIE_SIGNAL_HASH: u64 = 0xC0FF_EE5E_ED05_12E5;
+
+/// A sequence of bytes, each written with [`Hasher::write_u8`], which represents a signal (like
+/// [len_signal_hash], but for [Hasher]s that specialize only `write_u8`).
+///
+/// All bytes are distinct, so when a partial match breaks, the match can restart (only) at the
+/// current byte.
+pub const U8_STREAM_SIGNAL_HASH: [u8; 8] = [0xF7, 0x3A, 0x9C, 0x51, 0xE2, 0x0D, 0xB6, 0x48];
+
+#[cfg(feature = "hpe")]
 /// A fictitious slice length, indicating that a [`core::hash::Hash`] implementation submits a hash
-/// first (before signalling).
+/// first (before signalling). Written only with `chk-flow`, but reserved regardless.
 pub const LEN_SIGNAL_CHECK_FLOW_IS_SUBMIT_FIRST: usize = usize::MAX - 1;
-#[cfg(all(feature = "hpe", feature = "chk-flow"))]
+#[cfg(feature = "hpe")]
 /// A fictitious slice length, indicating that a [`core::hash::Hash`] implementation signals first
-/// (before submitting a hash).
+/// (before submitting a hash). Written only with `chk-flow`, but reserved regardless.
 pub const LEN_SIGNAL_CHECK_FLOW_IS_SIGNAL_FIRST: usize = usize::MAX - 2;
 
 #[cfg(any(feature = "mx", feature = "ndd"))]
-type U8Array = [u8; 3];
+/// Length (in bytes) of each signalling slice (and string slice). Signals are recognized by both
+/// their address and their length, so a user's legitimate slice that starts at the same address
+/// (but has a different length) is not misread as a signal. Longer signals make such aliasing less
+/// likely - at the cost of a bigger static.
+///
+/// The bytes are ASCII, so that they can serve as string slices, too.
+pub const SIGNAL_LEN: usize = 8;
+
+#[cfg(any(feature = "mx", feature = "ndd"))]
+type U8Array = [u8; 3 * SIGNAL_LEN];
+#[cfg(any(feature = "mx", feature = "ndd"))]
+const SIGNAL_BYTES: U8Array = *b"SIG-HASHSIG-SUBMSIG-SIGN";
+// So that str_full() doesn't need to validate at runtime.
+#[cfg(any(feature = "mx", feature = "ndd"))]
+const _: () = assert!(str::from_utf8(&SIGNAL_BYTES).is_ok());
+// An empty slice may share its address with the signal, so only a non-zero length tells them apart.
+#[cfg(any(feature = "mx", feature = "ndd"))]
+const _: () = assert!(SIGNAL_LEN > 0);
+// Shared by all threads. That's sound: nothing ever writes to it (SIG_MX is never locked), and
+// signals are recognized only by its address.
 #[cfg(feature = "mx")]
-static SIG_MX: Mutex<U8Array> = hint::black_box(Mutex::new([b'A', b'B', b'C']));
+static SIG_MX: Mutex<U8Array> = hint::black_box(Mutex::new(SIGNAL_BYTES));
 #[cfg(feature = "ndd")]
-static SIG_NDD: NonDeDuplicated<U8Array> = NonDeDuplicated::new([b'A', b'B', b'C']);
+static SIG_NDD: NonDeDuplicated<U8Array> = NonDeDuplicated::new(SIGNAL_BYTES);
 
 #[cfg(any(feature = "mx", feature = "ndd"))]
 #[inline(always)]
 fn str_full() -> &'static str {
     #[cfg(feature = "ndd")]
     let bytes = &*SIG_NDD;
+    // SAFETY: SIG_MX is never locked, so nothing ever writes through the pointer. The Mutex is
+    // static, so the reference lives for 'static.
     #[cfg(feature = "mx")]
     let bytes = unsafe { &*SIG_MX.data_ptr() as &U8Array };
     let bytes_slice = bytes.as_slice();
     #[cfg(feature = "ndd")]
     return str::from_utf8(bytes_slice).unwrap();
+    // SAFETY: The bytes are those of SIGNAL_BYTES (see above), which are validated as UTF-8 at
+    // compile time.
     #[cfg(feature = "mx")]
     return unsafe { str::from_utf8_unchecked(bytes_slice) };
 }
+/// The signal of `str` protocols (to pass to [`Hasher::write_str`]). It is recognized by its
+/// address (and length), not by its content.
 #[cfg(any(feature = "mx", feature = "ndd"))]
 #[inline(always)]
 pub fn str_signal_hash() -> &'static str {
-    #[cfg(feature = "ndd")]
-    return str_full().get(0..1).unwrap();
-    #[cfg(feature = "mx")]
-    return unsafe { str_full().get_unchecked(0..1) };
+    str_full().get(0..SIGNAL_LEN).unwrap()
 }
 #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "chk-flow"))]
 #[inline(always)]
 pub fn str_signal_check_flow_is_submit_first() -> &'static str {
-    #[cfg(feature = "ndd")]
-    return str_full().get(1..2).unwrap();
-    #[cfg(feature = "mx")]
-    return unsafe { str_full().get_unchecked(1..2) };
+    str_full().get(SIGNAL_LEN..2 * SIGNAL_LEN).unwrap()
 }
 #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "chk-flow"))]
 #[inline(always)]
 pub fn str_signal_check_flow_is_signal_first() -> &'static str {
-    #[cfg(feature = "ndd")]
-    return str_full().get(2..3).unwrap();
-    #[cfg(feature = "mx")]
-    return unsafe { str_full().get_unchecked(2..3) };
+    str_full().get(2 * SIGNAL_LEN..3 * SIGNAL_LEN).unwrap()
 }
 
+/// The signal of `u8s` protocols (to pass to [`Hasher::write`]). It is recognized by its address
+/// (and length), not by its content.
 #[cfg(any(feature = "mx", feature = "ndd"))]
 #[inline(always)]
 pub fn u8s_signal_hash() -> &'static [u8] {
@@ -96,30 +175,61 @@ fn ptr_signal_hash() -> *const u8 {
     #[cfg(feature = "mx")]
     return SIG_MX.data_ptr() as *const u8;
 }
+/// Whether the slice (or string slice) at `other` with length `len` is the signal of a hash.
+///
+/// The length is compared first: an empty slice (for example `&u8s_signal_hash()[..0]`) may point
+/// to the signal's address, but it is never the signal.
 #[cfg(any(feature = "mx", feature = "ndd"))]
 #[inline(always)]
-pub fn is_ptr_signal_hash(other: *const u8) -> bool {
-    ptr::eq(ptr_signal_hash(), other)
+pub fn is_ptr_signal_hash(other: *const u8, len: usize) -> bool {
+    len == SIGNAL_LEN && ptr::eq(ptr_signal_hash(), other)
 }
 #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "chk-flow"))]
 #[inline(always)]
-pub fn is_ptr_signal_check_flow_is_submit_first(other: *const u8) -> bool {
-    #[cfg(feature = "ndd")]
-    return ptr::eq(ptr_signal_hash().wrapping_add(1), other);
-    #[cfg(feature = "mx")]
-    return ptr::eq(unsafe { ptr_signal_hash().add(1) }, other);
+pub fn is_ptr_signal_check_flow_is_submit_first(other: *const u8, len: usize) -> bool {
+    len == SIGNAL_LEN && ptr::eq(ptr_signal_hash().wrapping_add(SIGNAL_LEN), other)
 }
 #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "chk-flow"))]
 #[inline(always)]
-pub fn is_ptr_signal_check_flow_is_signal_first(other: *const u8) -> bool {
-    #[cfg(feature = "ndd")]
-    return ptr::eq(ptr_signal_hash().wrapping_add(2), other);
-    #[cfg(feature = "mx")]
-    return ptr::eq(unsafe { ptr_signal_hash().add(2) }, other);
+pub fn is_ptr_signal_check_flow_is_signal_first(other: *const u8, len: usize) -> bool {
+    len == SIGNAL_LEN && ptr::eq(ptr_signal_hash().wrapping_add(2 * SIGNAL_LEN), other)
 }
 
+/// Whether a write is a `chk-flow` sentinel (of either flow) of protocol `PF`. `via` is how the
+/// write could signal: [SignalVia::Len] for [`Hasher::write_length_prefix`] of `_len` (then `_ptr`
+/// is ignored), [SignalVia::U8s] for [`Hasher::write`] and [SignalVia::Str] for
+/// [`Hasher::write_str`] of `_len` bytes at `_ptr`. Always `false` without `chk-flow`.
 #[inline(always)]
-fn signal<H: Hasher>(#[allow(non_snake_case)] PF: ProtocolFlags, _hasher: &mut H) {
+pub(crate) fn is_check_flow_sentinel(
+    #[allow(non_snake_case)] PF: ProtocolFlags,
+    via: SignalVia,
+    _ptr: *const u8,
+    _len: usize,
+) -> bool {
+    if !cfg!(feature = "chk-flow") || flags::signal_via(PF) != via {
+        return false;
+    }
+    match via {
+        #[cfg(all(feature = "hpe", feature = "chk-flow"))]
+        SignalVia::Len => {
+            _len == LEN_SIGNAL_CHECK_FLOW_IS_SUBMIT_FIRST
+                || _len == LEN_SIGNAL_CHECK_FLOW_IS_SIGNAL_FIRST
+        }
+        #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "chk-flow"))]
+        SignalVia::U8s | SignalVia::Str => {
+            is_ptr_signal_check_flow_is_submit_first(_ptr, _len)
+                || is_ptr_signal_check_flow_is_signal_first(_ptr, _len)
+        }
+        _ => false,
+    }
+}
+
+#[inline(always)]
+#[track_caller]
+pub(crate) fn signal<H: Hasher + ?Sized>(
+    #[allow(non_snake_case)] PF: ProtocolFlags,
+    _hasher: &mut H,
+) {
     match flags::signal_via(PF) {
         SignalVia::U8s => {
             #[cfg(any(feature = "mx", feature = "ndd"))]
@@ -129,7 +239,7 @@ fn signal<H: Hasher>(#[allow(non_snake_case)] PF: ProtocolFlags, _hasher: &mut H
         }
         SignalVia::Len => {
             #[cfg(feature = "hpe")]
-            _hasher.write_length_prefix(LEN_SIGNAL_HASH);
+            _hasher.write_length_prefix(len_signal_hash(PF));
             #[cfg(not(feature = "hpe"))]
             unreachable!()
         }
@@ -139,11 +249,70 @@ fn signal<H: Hasher>(#[allow(non_snake_case)] PF: ProtocolFlags, _hasher: &mut H
             #[cfg(not(all(any(feature = "mx", feature = "ndd"), feature = "hpe")))]
             unreachable!()
         }
+        SignalVia::LenCookie => {
+            #[cfg(feature = "hpe")]
+            {
+                _hasher.write_length_prefix(0);
+                _hasher.write_u64(LEN_COOKIE_SIGNAL_HASH);
+            }
+            #[cfg(not(feature = "hpe"))]
+            unreachable!()
+        }
+        SignalVia::Usize => {
+            _hasher.write_usize(USIZE_SIGNAL_HASH);
+            _hasher.write_u64(USIZE_COOKIE_SIGNAL_HASH);
+        }
+        SignalVia::LenCarrier => {
+            #[cfg(feature = "hpe")]
+            _hasher.write_length_prefix(len_signal_hash(PF));
+            #[cfg(not(feature = "hpe"))]
+            unreachable!()
+        }
+        SignalVia::Trusted => {}
+        SignalVia::U8Stream => {
+            for byte in U8_STREAM_SIGNAL_HASH {
+                _hasher.write_u8(byte);
+            }
+        }
     };
 }
 
+/// The cookie that completes a signal of protocol `PF`: For `len_cookie` and `usize` protocols
+/// only.
 #[inline(always)]
-fn submit_hash<H: Hasher, const PF: ProtocolFlags>(hasher: &mut H, hash: u64) {
+pub(crate) const fn cookie(#[allow(non_snake_case)] PF: ProtocolFlags) -> u64 {
+    match flags::signal_via(PF) {
+        SignalVia::LenCookie => {
+            #[cfg(feature = "hpe")]
+            {
+                LEN_COOKIE_SIGNAL_HASH
+            }
+            #[cfg(not(feature = "hpe"))]
+            unreachable!()
+        }
+        SignalVia::Usize => USIZE_COOKIE_SIGNAL_HASH,
+        SignalVia::U8s
+        | SignalVia::Len
+        | SignalVia::Str
+        | SignalVia::Trusted
+        | SignalVia::U8Stream
+        | SignalVia::LenCarrier => unreachable!(),
+    }
+}
+
+#[inline(always)]
+#[track_caller]
+pub(crate) fn submit_hash<H: Hasher + ?Sized>(
+    #[allow(non_snake_case)] PF: ProtocolFlags,
+    hasher: &mut H,
+    hash: u64,
+) {
+    #[cfg(feature = "hpe")]
+    if flags::is_signal_via_len_carrier(PF) {
+        hasher.write_length_prefix((hash >> 32) as usize);
+        hasher.write_length_prefix(hash as u32 as usize);
+        return;
+    }
     match flags::hash_via(PF) {
         HashVia::U64 => {
             hasher.write_u64(hash);
@@ -157,6 +326,9 @@ fn submit_hash<H: Hasher, const PF: ProtocolFlags>(hasher: &mut H, hash: u64) {
         HashVia::I128 => {
             hasher.write_i128(hash as i128);
         }
+        HashVia::Bytes => {
+            hasher.write(&hash.to_le_bytes());
+        }
     };
 }
 
@@ -177,25 +349,89 @@ fn submit_hash<H: Hasher, const PF: ProtocolFlags>(hasher: &mut H, hash: u64) {
 ///   the intended hash comes from that other type's [`core::hash::Hash::hash`] on a [Hasher]
 ///   created by the same [core::hash::BuildHasher].)
 ///
+/// The injected `hash` can come from anywhere - for example, from a different hash function (of a
+/// different hasher family) computed out-of-band. It fully replaces whatever the [Hasher] wrapped
+/// by [crate::hasher::SignalledInjectionHasher] would produce. See module `examples` (with `std`
+/// feature).
+///
 /// Extra validation of signalling in the user's [core::hash::Hash] implementation is done ONLY in
 /// when built with relevant cargo features (`chk-flow`, `chk-hash`, `chk`).
-pub fn inject<H: Hasher, const PF: ProtocolFlags>(hasher: &mut H, hash: u64) {
+#[track_caller]
+pub fn inject<H: Hasher + ?Sized, const PF: ProtocolFlags>(hasher: &mut H, hash: u64) {
+    signal_and_submit::<_, PF>(hasher, hash);
+    // Check that finish() does return the signalled hash. We do this BEFORE
+    // chk-flow-based checks (if any).
+    #[cfg(feature = "chk-hash")]
+    assert_eq!(hasher.finish(), hash);
+
+    #[cfg(feature = "chk-flow")]
+    check_flow(PF, hasher);
+}
+
+/// Like [inject], but for a hash that [crate::hasher::SignalledInjectionHasher] combines with
+/// ordinary data written before (see [crate::PartialInject]). For signal-first protocols only.
+///
+/// It signals twice in a row: A single signal after ordinary data is a misuse of [inject].
+/// Hence no `chk-hash` check, either.
+#[track_caller]
+pub(crate) fn inject_combined<H: Hasher + ?Sized, const PF: ProtocolFlags>(
+    hasher: &mut H,
+    hash: u64,
+) {
+    debug_assert!(flags::is_signal_first(PF));
+    signal(PF, hasher);
+    inject_without_chk_hash::<_, PF>(hasher, hash);
+}
+
+/// Like [inject], but without the `chk-hash` check.
+#[track_caller]
+pub(crate) fn inject_without_chk_hash<H: Hasher + ?Sized, const PF: ProtocolFlags>(
+    hasher: &mut H,
+    hash: u64,
+) {
+    signal_and_submit::<_, PF>(hasher, hash);
+    #[cfg(feature = "chk-flow")]
+    check_flow(PF, hasher);
+}
+
+#[inline(always)]
+#[track_caller]
+fn signal_and_submit<H: Hasher + ?Sized, const PF: ProtocolFlags>(hasher: &mut H, hash: u64) {
+    #[cfg(feature = "chk")]
+    const {
+        flags::assert_usable(PF)
+    };
+    signal_and_submit_with_flags(PF, hasher, hash);
+}
+
+#[inline(always)]
+#[track_caller]
+fn signal_and_submit_with_flags<H: Hasher + ?Sized>(
+    #[allow(non_snake_case)] PF: ProtocolFlags,
+    hasher: &mut H,
+    hash: u64,
+) {
     match flags::flow(PF) {
         Flow::SubmitFirst => {
-            submit_hash::<_, PF>(hasher, hash);
+            submit_hash(PF, hasher, hash);
             signal(PF, hasher);
         }
         Flow::SignalFirst => {
             signal(PF, hasher);
-            submit_hash::<_, PF>(hasher, hash);
+            submit_hash(PF, hasher, hash);
         }
     }
-    // Check that finish() does return the signalled hash. We do this BEFORE
-    // chk-flow-based checks (if any).
-    #[cfg(feature = "chk-hash")]
-    assert_eq!(hasher.finish(), hash);
+}
 
-    #[cfg(feature = "chk-flow")]
+/// Write the `chk-flow` sentinel (if any) for protocol `PF`.
+#[cfg(feature = "chk-flow")]
+// Without `mx`, `ndd` or `hpe`, no enabled protocol has a sentinel.
+#[cfg_attr(
+    not(any(feature = "mx", feature = "ndd", feature = "hpe")),
+    allow(unused_variables)
+)]
+#[inline(always)]
+fn check_flow<H: Hasher + ?Sized>(#[allow(non_snake_case)] PF: ProtocolFlags, hasher: &mut H) {
     match flags::flow(PF) {
         Flow::SubmitFirst => {
             match flags::signal_via(PF) {
@@ -218,6 +454,12 @@ pub fn inject<H: Hasher, const PF: ProtocolFlags>(hasher: &mut H, hash: u64) {
                     #[cfg(not(all(any(feature = "mx", feature = "ndd"), feature = "hpe")))]
                     unreachable!()
                 }
+                // No check-flow sentinels for these.
+                SignalVia::Trusted
+                | SignalVia::U8Stream
+                | SignalVia::LenCookie
+                | SignalVia::Usize
+                | SignalVia::LenCarrier => {}
             };
         }
         Flow::SignalFirst => {
@@ -240,7 +482,341 @@ pub fn inject<H: Hasher, const PF: ProtocolFlags>(hasher: &mut H, hash: u64) {
                     #[cfg(not(all(any(feature = "mx", feature = "ndd"), feature = "hpe")))]
                     unreachable!()
                 }
+                // No check-flow sentinels for these.
+                SignalVia::Trusted
+                | SignalVia::U8Stream
+                | SignalVia::LenCookie
+                | SignalVia::Usize
+                | SignalVia::LenCarrier => {}
             };
         }
     }
 }
+
+/// Like [inject], but with the protocol as a (runtime) value `flags`, rather than a const generic
+/// parameter. For code that can't use const generics (for example, macro-generated dispatch).
+///
+/// It branches on `flags` at runtime (unless the compiler inlines it with a constant `flags`).
+/// The [Hasher] (typically [crate::SignalledInjectionHasher]) must still use the same protocol.
+///
+/// # Panics
+///
+/// If `flags` is not valid, or if its protocol requires cargo features that are not enabled.
+#[track_caller]
+pub fn inject_with_flags<H: Hasher + ?Sized>(hasher: &mut H, flags: ProtocolFlags, hash: u64) {
+    flags::assert_usable(flags);
+    signal_and_submit_with_flags(flags, hasher, hash);
+    #[cfg(feature = "chk-hash")]
+    assert_eq!(hasher.finish(), hash);
+
+    #[cfg(feature = "chk-flow")]
+    check_flow(flags, hasher);
+}
+
+/// Like [inject], but for a [Hasher] trait object (for example, when a plugin architecture hands
+/// you a `&mut dyn Hasher`).
+///
+/// Through dynamic dispatch the compiler can NOT optimize away the (unused) write of the hash to
+/// the underlying [Hasher] in submit-first flows.
+#[track_caller]
+pub fn inject_dyn<const PF: ProtocolFlags>(hasher: &mut dyn Hasher, hash: u64) {
+    inject::<_, PF>(hasher, hash);
+}
+
+/// Like [inject], but for a hash stored as [NonZeroU64] (so that, for example, an `Option` of a key
+/// that stores it is no bigger than the key itself).
+///
+/// A zero hash can't be represented. Remap it (for example, to `1`) before storing it.
+#[track_caller]
+pub fn inject_nonzero<H: Hasher + ?Sized, const PF: ProtocolFlags>(
+    hasher: &mut H,
+    hash: NonZeroU64,
+) {
+    inject::<_, PF>(hasher, hash.get());
+}
+
+/// Mix `tag` into `hash`, as [inject_tagged] does. Use it where you need the resulting hash
+/// directly (for example, with `insert_by_hash`, if enabled by `raw-entry` feature).
+#[must_use]
+pub const fn tag_hash(hash: u64, tag: u64) -> u64 {
+    hash.rotate_left(32) ^ tag
+}
+
+/// Like [inject], but mix `TAG` into `hash` first (see [tag_hash]). So, key types with different
+/// tags occupy different hash spaces: Keys of different types that have the same raw hash (and
+/// share a map, for example, through an `enum`) don't collide.
+///
+/// Both storage and lookup MUST use the same `TAG` for the same key type. Otherwise the key is not
+/// found.
+#[track_caller]
+pub fn inject_tagged<H: Hasher + ?Sized, const PF: ProtocolFlags, const TAG: u64>(
+    hasher: &mut H,
+    hash: u64,
+) {
+    inject::<_, PF>(hasher, tag_hash(hash, TAG));
+}
+
+#[cfg(test)]
+mod tests_nonzero {
+    use super::*;
+    use crate::SignalledInjectionBuildHasher;
+    use core::hash::{BuildHasher, Hash};
+    use core::mem::size_of;
+    use std::hash::RandomState;
+
+    const PF: ProtocolFlags = crate::new::trusted::u64();
+
+    struct Key(NonZeroU64);
+    impl Hash for Key {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            inject_nonzero::<_, PF>(state, self.0);
+        }
+    }
+
+    #[test]
+    fn round_trip_and_niche() {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        for hash in [1, 0x0123_4567_89AB_CDEF, u64::MAX] {
+            assert_eq!(build.hash_one(Key(NonZeroU64::new(hash).unwrap())), hash);
+        }
+        assert_eq!(size_of::<Option<Key>>(), size_of::<Key>());
+    }
+}
+
+#[cfg(test)]
+mod tests_tagged {
+    use super::*;
+    use crate::SignalledInjectionBuildHasher;
+    use core::hash::{BuildHasher, Hash};
+    use std::collections::HashSet;
+    use std::hash::RandomState;
+
+    const PF: ProtocolFlags = crate::new::trusted::u64();
+    const TAG_USER: u64 = 1;
+    const TAG_ORDER: u64 = 2;
+
+    /// Two kinds of keys that share a set. Their raw hashes are their ids, so they can be equal.
+    #[derive(PartialEq, Eq, Debug)]
+    enum Key {
+        User(u64),
+        Order(u64),
+    }
+    impl Hash for Key {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            match *self {
+                Key::User(id) => inject_tagged::<_, PF, TAG_USER>(state, id),
+                Key::Order(id) => inject_tagged::<_, PF, TAG_ORDER>(state, id),
+            }
+        }
+    }
+
+    #[test]
+    fn same_raw_hash_different_tags() {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        for id in [0, 1, 0x0123_4567_89AB_CDEF, u64::MAX] {
+            let user = build.hash_one(Key::User(id));
+            let order = build.hash_one(Key::Order(id));
+            assert_eq!(user, tag_hash(id, TAG_USER));
+            assert_eq!(order, tag_hash(id, TAG_ORDER));
+            assert_ne!(user, order);
+        }
+
+        let mut set = HashSet::with_hasher(build);
+        for id in 0..100 {
+            assert!(set.insert(Key::User(id)));
+            assert!(set.insert(Key::Order(id)));
+        }
+        assert_eq!(set.len(), 200);
+        assert!(set.contains(&Key::User(7)));
+        assert!(set.contains(&Key::Order(7)));
+        assert!(!set.contains(&Key::Order(100)));
+    }
+}
+
+#[cfg(all(test, feature = "hpe"))]
+mod tests {
+    use super::*;
+    use crate::SignalledInjectionBuildHasher;
+    use core::hash::BuildHasher;
+    use std::boxed::Box;
+    use std::hash::RandomState;
+
+    fn inject_dyn_into_boxed<const PF: ProtocolFlags>() {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new());
+        let mut hasher: Box<dyn Hasher> = Box::new(build.build_hasher());
+        inject_dyn::<PF>(&mut *hasher, 0xABCD_EF01_2345_6789);
+        assert_eq!(hasher.finish(), 0xABCD_EF01_2345_6789);
+    }
+
+    #[test]
+    fn inject_dyn_signal_first() {
+        inject_dyn_into_boxed::<{ crate::new::len::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn inject_dyn_submit_first() {
+        inject_dyn_into_boxed::<{ crate::new::len::submit_first::u64() }>();
+    }
+}
+
+#[cfg(all(test, feature = "mx"))]
+mod tests_u8s {
+    use super::*;
+    use crate::SignalledInjectionBuildHasher;
+    use core::hash::BuildHasher;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    fn u8s_round_trip<const PF: ProtocolFlags>() {
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(BuildHasherDefault::<
+            DefaultHasher,
+        >::default());
+        let mut hasher = build.build_hasher();
+        inject::<_, PF>(&mut hasher, 0x0123_4567_89AB_CDEF);
+        assert_eq!(hasher.finish(), 0x0123_4567_89AB_CDEF);
+    }
+
+    #[test]
+    fn u8s_round_trip_signal_first() {
+        assert_eq!(u8s_signal_hash().len(), SIGNAL_LEN);
+        u8s_round_trip::<{ crate::new::u8s::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn str_signals_are_ascii_of_signal_len() {
+        let signals = [
+            str_signal_hash(),
+            #[cfg(feature = "chk-flow")]
+            str_signal_check_flow_is_submit_first(),
+            #[cfg(feature = "chk-flow")]
+            str_signal_check_flow_is_signal_first(),
+        ];
+        for signal in signals {
+            assert_eq!(signal.len(), SIGNAL_LEN);
+            assert!(signal.is_ascii());
+            assert_eq!(str::from_utf8(signal.as_bytes()), Ok(signal));
+        }
+    }
+
+    #[test]
+    fn u8s_round_trip_submit_first() {
+        u8s_round_trip::<{ crate::new::u8s::submit_first::u64() }>();
+    }
+
+    /// Keys that hash lengths and string slices (through `Hasher::write_length_prefix` and
+    /// `Hasher::write_str`, implemented by the wrapper only with `hpe`) are ordinary data.
+    #[test]
+    fn str_and_slice_keys_are_data() {
+        const PF: ProtocolFlags = crate::new::u8s::submit_first::u64();
+        let plain_build = BuildHasherDefault::<DefaultHasher>::default();
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(plain_build.clone());
+
+        let key = ("abc", [1u64, 2].as_slice(), std::vec::Vec::<u8>::new());
+        assert_eq!(build.hash_one(&key), plain_build.hash_one(&key));
+    }
+
+    /// Slices with the same content as the signal (but a different address), or with the same
+    /// address (but a different length, including empty slices), are ordinary data.
+    fn u8s_lookalikes_are_data<const PF: ProtocolFlags>() {
+        let plain_build = BuildHasherDefault::<DefaultHasher>::default();
+        let build = SignalledInjectionBuildHasher::<_, _, PF>::new(plain_build.clone());
+
+        let copy: [u8; SIGNAL_LEN] = u8s_signal_hash().try_into().unwrap();
+        let signal = u8s_signal_hash();
+        let lookalikes: [&[u8]; 5] = [
+            &copy[..],
+            &signal[..1],
+            &[],
+            &signal[..0],
+            &signal[SIGNAL_LEN..],
+        ];
+        for bytes in lookalikes {
+            let mut plain = plain_build.build_hasher();
+            plain.write(bytes);
+            plain.write_u64(1);
+
+            let mut hasher = build.build_hasher();
+            hasher.write(bytes);
+            hasher.write_u64(1);
+            assert_eq!(hasher.finish(), plain.finish());
+        }
+    }
+
+    #[test]
+    fn u8s_lookalikes_are_data_signal_first() {
+        u8s_lookalikes_are_data::<{ crate::new::u8s::signal_first::u64() }>();
+    }
+
+    #[test]
+    fn u8s_lookalikes_are_data_submit_first() {
+        u8s_lookalikes_are_data::<{ crate::new::u8s::submit_first::u64() }>();
+    }
+}
+
+/// With `chk-hash`, [inject] asserts that the [Hasher] returns the injected hash - but
+/// [crate::testing::RecordingHasher] does not.
+#[cfg(all(test, not(feature = "chk-hash")))]
+mod tests_with_flags {
+    use super::*;
+    use crate::testing::RecordingHasher;
+
+    fn same_calls<const PF: ProtocolFlags>() {
+        for hash in [0, 1, 0x0123_4567_89AB_CDEF, u64::MAX] {
+            let mut generic = RecordingHasher::default();
+            inject::<_, PF>(&mut generic, hash);
+            let mut with_flags = RecordingHasher::default();
+            inject_with_flags(&mut with_flags, PF, hash);
+            assert_eq!(with_flags.calls, generic.calls);
+        }
+    }
+
+    #[test]
+    fn same_calls_trusted() {
+        same_calls::<{ crate::new::trusted::u64() }>();
+        same_calls::<{ crate::new::trusted::i128() }>();
+    }
+
+    #[test]
+    fn same_calls_u8_stream() {
+        same_calls::<{ crate::new::u8_stream::signal_first::u64() }>();
+        same_calls::<{ crate::new::u8_stream::signal_first::i64() }>();
+        same_calls::<{ crate::new::u8_stream::submit_first::u128() }>();
+        same_calls::<{ crate::new::u8_stream::submit_first::bytes() }>();
+    }
+
+    #[test]
+    fn same_calls_usize() {
+        same_calls::<{ crate::new::usize::signal_first::i128() }>();
+        same_calls::<{ crate::new::usize::submit_first::u64() }>();
+    }
+
+    #[cfg(feature = "hpe")]
+    #[test]
+    fn same_calls_len() {
+        same_calls::<{ crate::new::len::signal_first::u64() }>();
+        same_calls::<{ crate::new::len::submit_first::i64() }>();
+        same_calls::<{ crate::new::len_cookie::signal_first::bytes() }>();
+        same_calls::<{ crate::new::len_cookie::submit_first::u128() }>();
+        same_calls::<{ crate::new::len_carrier::u64() }>();
+    }
+
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    #[test]
+    fn same_calls_u8s() {
+        same_calls::<{ crate::new::u8s::signal_first::u64() }>();
+        same_calls::<{ crate::new::u8s::submit_first::i128() }>();
+    }
+
+    #[cfg(all(any(feature = "mx", feature = "ndd"), feature = "hpe"))]
+    #[test]
+    fn same_calls_str() {
+        same_calls::<{ crate::new::str::signal_first::u64() }>();
+        same_calls::<{ crate::new::str::submit_first::bytes() }>();
+    }
+
+    #[cfg(not(feature = "flags"))]
+    #[test]
+    #[should_panic(expected = "Invalid ProtocolFlags.")]
+    fn invalid_flags() {
+        inject_with_flags(&mut RecordingHasher::default(), 0b000_0111, 1);
+    }
+}
@@ -0,0 +1,49 @@
+//! Signals, for [core::hash::Hash] implementations that signal manually, rather than calling
+//! [crate::inject].
+//!
+//! Signal exactly as [crate::inject] would for the protocol, and submit the hash with the
+//! `Hasher::write_xxx` that the protocol expects (see [crate::new]): either the signal first and
+//! then the hash, or the other way around. Do not write anything else to the same
+//! [core::hash::Hasher]. Protocols created by `bytes()` constructors take the hash as
+//! `write(&hash.to_le_bytes())`. The `len_carrier` protocol takes it as two more
+//! `write_length_prefix` calls: of its high 32 bits, and then of its low 32 bits.
+//!
+//! | Protocols ([crate::new]) | Signal                                                           |
+//! |--------------------------|------------------------------------------------------------------|
+//! | `u8s`                    | `write(u8s_signal_hash())`                                       |
+//! | `len`                    | `write_length_prefix(len_signal_hash(PF))`                       |
+//! | `len_cookie`             | `write_length_prefix(0)`, `write_u64(LEN_COOKIE_SIGNAL_HASH)`    |
+//! | `len_carrier`            | `write_length_prefix(len_signal_hash(PF))`                       |
+//! | `str`                    | `write_str(str_signal_hash())`                                   |
+//! | `u8_stream`              | `write_u8(byte)` for each byte of [U8_STREAM_SIGNAL_HASH]        |
+//! | `usize`                  | `write_usize(usize::MAX)`, `write_u64(USIZE_COOKIE_SIGNAL_HASH)` |
+//! | `trusted`                | none                                                             |
+//!
+//! ```
+//! use core::hash::{BuildHasher, Hash, Hasher};
+//! use hash_injector::signals::U8_STREAM_SIGNAL_HASH;
+//! use hash_injector::{ProtocolFlags, SignalledInjectionBuildHasher, new};
+//!
+//! const PF: ProtocolFlags = new::u8_stream::signal_first::u64();
+//!
+//! struct Key {
+//!     hash: u64,
+//! }
+//! impl Hash for Key {
+//!     fn hash<H: Hasher>(&self, state: &mut H) {
+//!         for byte in U8_STREAM_SIGNAL_HASH {
+//!             state.write_u8(byte);
+//!         }
+//!         state.write_u64(self.hash);
+//!     }
+//! }
+//!
+//! let build = SignalledInjectionBuildHasher::<_, _, PF>::new(std::hash::RandomState::new());
+//! assert_eq!(build.hash_one(Key { hash: 42 }), 42);
+//! ```
+
+#[cfg(feature = "hpe")]
+pub use crate::signal::{LEN_COOKIE_SIGNAL_HASH, len_signal_hash};
+#[cfg(any(feature = "mx", feature = "ndd"))]
+pub use crate::signal::{SIGNAL_LEN, str_signal_hash, u8s_signal_hash};
+pub use crate::signal::{U8_STREAM_SIGNAL_HASH, USIZE_COOKIE_SIGNAL_HASH, USIZE_SIGNAL_HASH};
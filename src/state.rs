@@ -1,7 +1,7 @@
+use core::fmt::{self, Debug, Formatter};
+
 use crate::ProtocolFlags;
 use crate::flags;
-#[cfg(feature = "chk-details")]
-use core::fmt::Arguments;
 
 #[allow(private_interfaces)]
 pub type SignalStateKind = SignalStateKindImpl;
@@ -45,47 +45,122 @@ impl SignalStateKindImpl {
                 | (Self::HashReceived, Self::HashReceived)
         )
     }
-}
-#[cfg(feature = "chk-details")]
-impl SignalStateKindImpl {
-    /// For use in [Arguments]/
-    const fn type_and_variant(&self) -> &'static str {
+    const fn name(&self) -> &'static str {
         match self {
-            Self::NothingWritten => "SignalStateKindImpl::NothingWritten",
-            Self::WrittenOrdinaryHash => "SignalStateKindImpl::WrittenOrdinaryHash",
-            Self::SignalledProposalComing => "SignalStateKindImpl::SignalledProposalComing",
-            Self::HashPossiblySubmitted => "SignalStateKindImpl::HashPossiblySubmitted",
-            Self::HashReceived => "SignalStateKindImpl::HashReceived",
+            Self::NothingWritten => "NothingWritten",
+            Self::WrittenOrdinaryHash => "WrittenOrdinaryHash",
+            Self::SignalledProposalComing => "SignalledProposalComing",
+            Self::HashPossiblySubmitted => "HashPossiblySubmitted",
+            Self::HashReceived => "HashReceived",
         }
     }
+    /// Whether [SignalState::hash] is valid.
+    const fn has_hash(&self) -> bool {
+        matches!(self, Self::HashPossiblySubmitted | Self::HashReceived)
+    }
+}
+/// Panic with message `$expected` (a string literal), followed by the actual state `$kind`. The
+/// messages are spelled out per variant, because a `const fn` can't format at runtime.
+#[cfg(feature = "chk-details")]
+macro_rules! panic_with_state {
+    ($expected:literal, $kind:expr) => {
+        match $kind {
+            SignalStateKindImpl::NothingWritten => panic!(concat!(
+                $expected,
+                ", but the state was: SignalStateKindImpl::NothingWritten."
+            )),
+            SignalStateKindImpl::WrittenOrdinaryHash => panic!(concat!(
+                $expected,
+                ", but the state was: SignalStateKindImpl::WrittenOrdinaryHash."
+            )),
+            SignalStateKindImpl::SignalledProposalComing => panic!(concat!(
+                $expected,
+                ", but the state was: SignalStateKindImpl::SignalledProposalComing."
+            )),
+            SignalStateKindImpl::HashPossiblySubmitted => panic!(concat!(
+                $expected,
+                ", but the state was: SignalStateKindImpl::HashPossiblySubmitted."
+            )),
+            SignalStateKindImpl::HashReceived => panic!(concat!(
+                $expected,
+                ", but the state was: SignalStateKindImpl::HashReceived."
+            )),
+        }
+    };
 }
 /// This used to be a data-carrying enum on its own, separate from SignalStateKind, NOT containing
 /// SignalStateKind, and carrying the possibly submitted/received hash in its variants. But, then we
 /// couldn't specify its variant integer values without fixing the representation, which would be
 /// limiting.
 ///
-/// Another advantage of separation is that [SignalStateKindImpl] has
-/// [SignalStateKindImpl::type_and_variant], helps with making
+/// Another advantage of separation is that matching on [SignalStateKindImpl] alone (see
+/// `panic_with_state`) helps with making
 /// [SignalState::assert_nothing_written_or_ordinary_hash] and
 /// [SignalState::assert_nothing_written_or_ordinary_hash_or_possibly_submitted] `const fn`. That
 /// allows us to validate them in [_CHECKS].
-#[derive(PartialEq, Eq, Debug)]
+///
+/// Its [Debug] output is stable, and the same regardless of cargo features: the name of the kind,
+/// plus the hash (in hex) only if the kind carries one. For example,
+/// `SignalState { kind: "HashReceived", hash: 0x2a }`, or `SignalState { kind: "NothingWritten" }`.
+#[derive(PartialEq, Eq)]
 pub struct SignalState {
     #[allow(private_interfaces)]
     pub kind: SignalStateKind,
     /// Only valid if [SignalState::kind] is appropriate.
     pub hash: u64,
+    // Bookkeeping of some protocols only. Except for `len_carrier_high`, it fits in what would
+    // otherwise be padding after `kind`. Not included in [Debug] output.
+    /// How many leading bytes of [crate::signal::U8_STREAM_SIGNAL_HASH] were written (with
+    /// `Hasher::write_u8`) so far. Used only by `u8_stream` protocols.
+    pub u8_stream_matched: u8,
+    /// Whether the most recent write may be the first half of a signal, which a cookie completes: a
+    /// zero length (with `Hasher::write_length_prefix`) for `len_cookie` protocols, or
+    /// [crate::signal::USIZE_SIGNAL_HASH] (with `Hasher::write_usize`) for `usize` protocols. Used
+    /// only by those protocols.
+    pub cookie_pending: bool,
+    /// For signal-first protocols: Whether ordinary data was written before the signal.
+    pub signalled_after_ordinary: bool,
+    /// For signal-first protocols: Whether the signal came twice in a row (as written by
+    /// `signal::inject_combined`) after ordinary data. Only then the injected hash is combined with
+    /// the result of the underlying `Hasher` (see [crate::PartialInject]).
+    pub combine_with_ordinary: bool,
+    /// For the `len_carrier` protocol: The high 32 bits of the hash, once they came (in a length,
+    /// right after the signal). Then the low 32 bits come next.
+    #[cfg(feature = "hpe")]
+    pub len_carrier_high: Option<u32>,
+}
+impl Debug for SignalState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("SignalState");
+        debug.field("kind", &self.kind.name());
+        if self.kind.has_hash() {
+            debug.field("hash", &format_args!("{:#x}", self.hash));
+        }
+        debug.finish()
+    }
 }
 impl SignalState {
-    // Constructors and mutators. (Again, in order of SignalStateKind's usual lifecycle.)
+    /// With no protocol bookkeeping.
     #[inline(always)]
-    pub const fn new_nothing_written() -> Self {
+    const fn new(kind: SignalStateKind, hash: u64) -> Self {
         Self {
-            kind: SignalStateKind::NothingWritten,
-            hash: 0,
+            kind,
+            hash,
+            u8_stream_matched: 0,
+            cookie_pending: false,
+            signalled_after_ordinary: false,
+            combine_with_ordinary: false,
+            #[cfg(feature = "hpe")]
+            len_carrier_high: None,
         }
     }
+    // Constructors and mutators. (Again, in order of SignalStateKind's usual lifecycle.)
+    #[inline(always)]
+    pub const fn new_nothing_written() -> Self {
+        Self::new(SignalStateKind::NothingWritten, 0)
+    }
     #[inline(always)]
+    #[track_caller]
     pub const fn set_written_ordinary_hash(&mut self) {
         #[cfg(feature = "chk")]
         if matches!(self.kind, SignalStateKind::HashReceived) {
@@ -100,9 +175,10 @@ impl SignalState {
     )]
     /// Set the state that it was signalled that a hash proposal is coming.
     ///
-    /// Requires `signal_first(PF)==true` - otherwise it panics in debug mode (regardless of, and
-    /// ignoring, `chk` feature).
+    /// Requires `signal_first(PF)==true`, and that the hash has not been received yet - otherwise
+    /// it panics in debug mode (regardless of, and ignoring, `chk` feature).
     #[inline(always)]
+    #[track_caller]
     pub const fn set_signalled_proposal_coming(
         &mut self,
         #[allow(non_snake_case)] PF: ProtocolFlags,
@@ -111,6 +187,10 @@ impl SignalState {
         if flags::is_submit_first(PF) {
             panic!("Supported only for ProtocolFlags that signal first.");
         }
+        #[cfg(debug_assertions)]
+        if matches!(self.kind, SignalStateKind::HashReceived) {
+            panic!("Signalled twice: the hash has been received already.");
+        }
         self.kind = SignalStateKind::SignalledProposalComing;
     }
     /// Set the state to contain the given `u64` as a possible hash.
@@ -126,26 +206,27 @@ impl SignalState {
         if flags::is_signal_first(PF) {
             panic!("Supported only for ProtocolFlags that submit first.");
         }
-        Self {
-            kind: SignalStateKind::HashPossiblySubmitted,
-            hash,
-        }
+        Self::new(SignalStateKind::HashPossiblySubmitted, hash)
     }
 
     #[cfg_attr(
         not(any(feature = "mx", feature = "ndd", feature = "hpe")),
         allow(dead_code)
     )]
+    /// Panics in debug mode (regardless of, and ignoring, `chk` feature) if the hash has been
+    /// received already - that is, if the hash was signalled twice.
     #[inline(always)]
+    #[track_caller]
     pub const fn set_hash_received(&mut self) {
+        #[cfg(debug_assertions)]
+        if matches!(self.kind, SignalStateKind::HashReceived) {
+            panic!("Signalled twice: the hash has been received already.");
+        }
         self.kind = SignalStateKind::HashReceived;
     }
     #[inline(always)]
     pub const fn new_hash_received(hash: u64) -> Self {
-        Self {
-            kind: SignalStateKind::HashReceived,
-            hash,
-        }
+        Self::new(SignalStateKind::HashReceived, hash)
     }
     // ------
 
@@ -155,6 +236,10 @@ impl SignalState {
         //@TODO replace with matches!(..)
         matches!(self.kind, SignalStateKind::NothingWritten)
     }
+    #[inline(always)]
+    pub const fn is_written_ordinary_hash(&self) -> bool {
+        matches!(self.kind, SignalStateKind::WrittenOrdinaryHash)
+    }
 
     #[cfg_attr(not(feature = "chk"), allow(dead_code))]
     #[inline(always)]
@@ -230,11 +315,8 @@ impl SignalState {
     }
     // ------
 
-    #[cfg_attr(
-        not(any(feature = "mx", feature = "ndd", feature = "hpe")),
-        allow(dead_code)
-    )]
     #[inline(always)]
+    #[track_caller]
     pub const fn assert_nothing_written(&self) {
         #[cfg(feature = "chk")]
         if !self.is_nothing_written() {
@@ -244,16 +326,15 @@ impl SignalState {
             }
             #[cfg(feature = "chk-details")]
             {
-                let args_parts: [&'static str; 2] = [
-                    "Expecting the state to be SignalStateKindImpl::NothingWritten, but the state was: {}.",
-                    self.kind.type_and_variant(),
-                ];
-                let args: Arguments = Arguments::new_const(&args_parts);
-                core::panicking::panic_fmt(args)
+                panic_with_state!(
+                    "Expecting the state to be SignalStateKindImpl::NothingWritten",
+                    self.kind
+                );
             }
         }
     }
     #[inline(always)]
+    #[track_caller]
     pub const fn assert_nothing_written_or_ordinary_hash(&self) {
         #[cfg(feature = "chk")]
         if !self.is_nothing_written_or_ordinary_hash() {
@@ -265,12 +346,10 @@ impl SignalState {
             }
             #[cfg(feature = "chk-details")]
             {
-                let args_parts: [&'static str; 2] = [
-                    "Expecting the state to be SignalStateKindImpl::NothingWritten or SignalStateKindImpl::WrittenOrdinaryHash, but the state was: {}.",
-                    self.kind.type_and_variant(),
-                ];
-                let args: Arguments = Arguments::new_const(&args_parts);
-                core::panicking::panic_fmt(args)
+                panic_with_state!(
+                    "Expecting the state to be SignalStateKindImpl::NothingWritten or SignalStateKindImpl::WrittenOrdinaryHash",
+                    self.kind
+                );
             }
         }
     }
@@ -278,6 +357,7 @@ impl SignalState {
     /// - no hash has been signalled (if we do signal first - before submitting), and
     /// - no hash has been received (regardless of whether we signal first, or submit first).
     #[inline(always)]
+    #[track_caller]
     pub const fn assert_nothing_written_or_ordinary_hash_or_possibly_submitted(
         &self,
         #[allow(non_snake_case)] _PF: ProtocolFlags,
@@ -293,18 +373,20 @@ impl SignalState {
                 }
                 #[cfg(feature = "chk-details")]
                 {
-                    let args_parts: [&'static str; 2] = [
-                        "Expecting the state to be SignalStateKindImpl::NothingWritten, or SignalStateKindImpl::WrittenOrdinaryHash, or SignalStateKindImpl::HashPossiblySubmitted (if applicable), but the state was: {}.",
-                        self.kind.type_and_variant(),
-                    ];
-                    let args: Arguments = Arguments::new_const(&args_parts);
-                    core::panicking::panic_fmt(args);
+                    panic_with_state!(
+                        "Expecting the state to be SignalStateKindImpl::NothingWritten, or SignalStateKindImpl::WrittenOrdinaryHash, or SignalStateKindImpl::HashPossiblySubmitted (if applicable)",
+                        self.kind
+                    );
                 }
             }
         }
     }
 }
 
+// The bookkeeping fits in the padding (see [SignalState]).
+#[cfg(not(feature = "hpe"))]
+const _: () = assert!(size_of::<SignalState>() == 2 * size_of::<u64>());
+
 const _CHECKS: () = {
     let nothing_written = SignalState::new_nothing_written();
     {
@@ -528,4 +610,23 @@ mod tests {
         //panic!("{}", core::env!("CARGO_CRATE_NAME"));
         //panic!("{}", core::env!("CARGO_BIN_NAME"));
     }
+
+    #[test]
+    fn debug() {
+        use std::format;
+        assert_eq!(
+            format!("{:?}", SignalState::new_nothing_written()),
+            r#"SignalState { kind: "NothingWritten" }"#
+        );
+        assert_eq!(
+            format!("{:?}", SignalState::new_hash_received(42)),
+            r#"SignalState { kind: "HashReceived", hash: 0x2a }"#
+        );
+        let mut state = SignalState::new_nothing_written();
+        state.set_written_ordinary_hash();
+        assert_eq!(
+            format!("{:?}", state),
+            r#"SignalState { kind: "WrittenOrdinaryHash" }"#
+        );
+    }
 }
@@ -0,0 +1,291 @@
+//! Test helpers. Also available to other crates (for testing or fuzzing their own
+//! [core::hash::Hash] implementations that inject hashes) with `testing` cargo feature.
+
+use crate::{ProtocolFlags, SignalledInjectionBuildHasher};
+use core::hash::{BuildHasher, BuildHasherDefault, Hasher};
+use std::hash::DefaultHasher;
+#[cfg(feature = "hpe")]
+use std::string::String;
+use std::vec::Vec;
+
+/// A call to a [Hasher] method, as recorded by [RecordingHasher].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Call {
+    Write(Vec<u8>),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Usize(usize),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    Isize(isize),
+    #[cfg(feature = "hpe")]
+    LengthPrefix(usize),
+    #[cfg(feature = "hpe")]
+    Str(String),
+}
+
+/// A [Hasher] that records all calls to its `write_xxx` methods, and forwards them to a
+/// [DefaultHasher].
+#[derive(Default, Debug)]
+pub struct RecordingHasher {
+    pub calls: Vec<Call>,
+    hasher: DefaultHasher,
+}
+
+impl RecordingHasher {
+    /// Call the same `write_xxx` methods, with the same arguments and in the same order, on
+    /// `hasher`.
+    ///
+    /// [Hasher::write] and [Hasher::write_str] are replayed with copies of the recorded slices.
+    /// Hence signals recognized by address (protocols in [crate::new::u8s] and [crate::new::str])
+    /// are NOT recognized when replayed.
+    pub fn replay(&self, hasher: &mut impl Hasher) {
+        for call in &self.calls {
+            match call {
+                Call::Write(bytes) => hasher.write(bytes),
+                Call::U8(i) => hasher.write_u8(*i),
+                Call::U16(i) => hasher.write_u16(*i),
+                Call::U32(i) => hasher.write_u32(*i),
+                Call::U64(i) => hasher.write_u64(*i),
+                Call::U128(i) => hasher.write_u128(*i),
+                Call::Usize(i) => hasher.write_usize(*i),
+                Call::I8(i) => hasher.write_i8(*i),
+                Call::I16(i) => hasher.write_i16(*i),
+                Call::I32(i) => hasher.write_i32(*i),
+                Call::I64(i) => hasher.write_i64(*i),
+                Call::I128(i) => hasher.write_i128(*i),
+                Call::Isize(i) => hasher.write_isize(*i),
+                #[cfg(feature = "hpe")]
+                Call::LengthPrefix(len) => hasher.write_length_prefix(*len),
+                #[cfg(feature = "hpe")]
+                Call::Str(s) => hasher.write_str(s),
+            }
+        }
+    }
+}
+
+impl Hasher for RecordingHasher {
+    fn finish(&self) -> u64 {
+        self.hasher.finish()
+    }
+    fn write(&mut self, bytes: &[u8]) {
+        self.calls.push(Call::Write(bytes.to_vec()));
+        self.hasher.write(bytes);
+    }
+    fn write_u8(&mut self, i: u8) {
+        self.calls.push(Call::U8(i));
+        self.hasher.write_u8(i);
+    }
+    fn write_u16(&mut self, i: u16) {
+        self.calls.push(Call::U16(i));
+        self.hasher.write_u16(i);
+    }
+    fn write_u32(&mut self, i: u32) {
+        self.calls.push(Call::U32(i));
+        self.hasher.write_u32(i);
+    }
+    fn write_u64(&mut self, i: u64) {
+        self.calls.push(Call::U64(i));
+        self.hasher.write_u64(i);
+    }
+    fn write_u128(&mut self, i: u128) {
+        self.calls.push(Call::U128(i));
+        self.hasher.write_u128(i);
+    }
+    fn write_usize(&mut self, i: usize) {
+        self.calls.push(Call::Usize(i));
+        self.hasher.write_usize(i);
+    }
+    fn write_i8(&mut self, i: i8) {
+        self.calls.push(Call::I8(i));
+        self.hasher.write_i8(i);
+    }
+    fn write_i16(&mut self, i: i16) {
+        self.calls.push(Call::I16(i));
+        self.hasher.write_i16(i);
+    }
+    fn write_i32(&mut self, i: i32) {
+        self.calls.push(Call::I32(i));
+        self.hasher.write_i32(i);
+    }
+    fn write_i64(&mut self, i: i64) {
+        self.calls.push(Call::I64(i));
+        self.hasher.write_i64(i);
+    }
+    fn write_i128(&mut self, i: i128) {
+        self.calls.push(Call::I128(i));
+        self.hasher.write_i128(i);
+    }
+    fn write_isize(&mut self, i: isize) {
+        self.calls.push(Call::Isize(i));
+        self.hasher.write_isize(i);
+    }
+    #[cfg(feature = "hpe")]
+    fn write_length_prefix(&mut self, len: usize) {
+        self.calls.push(Call::LengthPrefix(len));
+        self.hasher.write_length_prefix(len);
+    }
+    #[cfg(feature = "hpe")]
+    fn write_str(&mut self, s: &str) {
+        self.calls.push(Call::Str(s.into()));
+        self.hasher.write_str(s);
+    }
+}
+
+/// A minimalist [Hasher] (FNV-1a) that meaningfully implements only [Hasher::write_u8]. All other
+/// writes go through it, byte by byte.
+#[derive(Clone, Copy, Debug)]
+pub struct U8OnlyHasher(u64);
+
+impl Default for U8OnlyHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for U8OnlyHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write_u8(byte);
+        }
+    }
+    fn write_u8(&mut self, i: u8) {
+        self.0 = (self.0 ^ i as u64).wrapping_mul(0x0100_0000_01b3);
+    }
+}
+
+/// A deterministic [Hasher] (FNV-1a) with no seed and no entropy: the same writes give the same
+/// [Hasher::finish] across runs. For golden-value tests of ordinary (non-injected) keys.
+///
+/// Multi-byte integers are written in native byte order (as by [Hasher]'s default methods), so
+/// golden values of those are platform-specific. Do NOT use it for keys controlled by third
+/// parties (Hash DoS).
+#[derive(Clone, Copy, Debug)]
+pub struct FixedHasher(u64);
+
+impl Default for FixedHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FixedHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// [core::hash::BuildHasher] of [FixedHasher]. Use it with
+/// [crate::SignalledInjectionBuildHasher::new].
+pub type FixedBuildHasher = BuildHasherDefault<FixedHasher>;
+
+/// Wrap `build` in [SignalledInjectionBuildHasher] for protocol `PF`. For each of `hashes`:
+/// [crate::inject] it, and assert that [Hasher::finish] (and
+/// [SignalledInjectionBuildHasher::hash_one_injected]) return it.
+pub fn assert_round_trip<B: BuildHasher, const PF: ProtocolFlags>(build: B, hashes: &[u64]) {
+    let build = SignalledInjectionBuildHasher::<_, _, PF>::new(build);
+    for &hash in hashes {
+        let mut hasher = build.build_hasher();
+        crate::inject::<_, PF>(&mut hasher, hash);
+        assert_eq!(hasher.finish(), hash);
+        assert_eq!(build.hash_one_injected(hash), hash);
+    }
+}
+
+/// A sequence of writes to a [Hasher], for [assert_passthrough].
+pub type Writes<'a> = &'a dyn Fn(&mut dyn Hasher);
+
+/// Assert that each of `writes` (of ordinary data only, or of incomplete signals) gives the same
+/// [Hasher::finish] when written to a [Hasher] built by `build`, as when written to one built by
+/// `build` wrapped in [SignalledInjectionBuildHasher] for protocol `PF`.
+pub fn assert_passthrough<B: BuildHasher + Clone, const PF: ProtocolFlags>(
+    build: B,
+    writes: &[Writes],
+) {
+    let injection_build = SignalledInjectionBuildHasher::<_, _, PF>::new(build.clone());
+    for write in writes {
+        let mut plain = build.build_hasher();
+        write(&mut plain);
+        let mut hasher = injection_build.build_hasher();
+        write(&mut hasher);
+        assert_eq!(hasher.finish(), plain.finish());
+    }
+}
+
+/// With `chk-hash`, [crate::inject] asserts that the [Hasher] returns the injected hash - but
+/// [RecordingHasher] does not.
+#[cfg(all(test, not(feature = "chk-hash")))]
+mod tests {
+    use super::*;
+    use crate::SignalledInjectionHasher;
+
+    fn replay_injects<const PF: ProtocolFlags>() {
+        let mut recording = RecordingHasher::default();
+        crate::inject::<_, PF>(&mut recording, 0xABCD);
+
+        let mut hasher = SignalledInjectionHasher::<RecordingHasher, PF>::default();
+        recording.replay(&mut hasher);
+        assert_eq!(hasher.finish(), 0xABCD);
+    }
+
+    #[test]
+    fn replay_injects_u8_stream() {
+        replay_injects::<{ crate::new::u8_stream::signal_first::u64() }>();
+        replay_injects::<{ crate::new::u8_stream::submit_first::i128() }>();
+    }
+
+    #[test]
+    fn replay_injects_usize() {
+        replay_injects::<{ crate::new::usize::signal_first::u64() }>();
+        replay_injects::<{ crate::new::usize::submit_first::i64() }>();
+    }
+
+    #[cfg(feature = "hpe")]
+    #[test]
+    fn replay_injects_len() {
+        replay_injects::<{ crate::new::len::signal_first::u128() }>();
+        replay_injects::<{ crate::new::len_cookie::submit_first::u64() }>();
+        replay_injects::<{ crate::new::len_carrier::u64() }>();
+    }
+
+    /// FNV-1a test vectors.
+    #[test]
+    fn fixed_golden() {
+        const PF: ProtocolFlags = crate::new::usize::signal_first::u64();
+        let build =
+            crate::SignalledInjectionBuildHasher::<_, _, PF>::new(FixedBuildHasher::default());
+        let mut hasher = build.build_hasher();
+        hasher.write(b"");
+        assert_eq!(hasher.finish(), 0xcbf2_9ce4_8422_2325);
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+
+        let mut hasher = build.build_hasher();
+        hasher.write(b"foobar");
+        assert_eq!(hasher.finish(), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn fixed_injects() {
+        const PF: ProtocolFlags = crate::new::u8_stream::submit_first::u64();
+        let build =
+            crate::SignalledInjectionBuildHasher::<_, _, PF>::new(FixedBuildHasher::default());
+        let mut hasher = build.build_hasher();
+        crate::inject::<_, PF>(&mut hasher, 0xABCD);
+        assert_eq!(hasher.finish(), 0xABCD);
+    }
+}
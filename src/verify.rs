@@ -0,0 +1,127 @@
+use core::hash::{BuildHasher, Hasher};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::ProtocolFlags;
+
+/// A [BuildHasher] that checks its protocol `PF` the first time it builds a [Hasher]: that
+/// [Hasher]s built by `B` return hashes injected as per `PF`. After that it trusts the protocol.
+///
+/// That catches gross misconfiguration (for example, `B` that is not a
+/// [crate::SignalledInjectionBuildHasher], or that is one of a different protocol) at startup, at
+/// near-zero steady-state cost. (Unlike `chk-hash`, which checks every injection.)
+///
+/// If the check fails, [BuildHasher::build_hasher] panics, describing the protocol.
+///
+/// ```
+/// use core::hash::BuildHasher;
+/// use hash_injector::{ProtocolFlags, SignalledInjectionBuildHasher, VerifyOnceBuildHasher, new};
+///
+/// const PF: ProtocolFlags = new::usize::submit_first::u64();
+/// let build = VerifyOnceBuildHasher::<_, PF>::new(SignalledInjectionBuildHasher::<_, _, PF>::new(
+///     std::hash::RandomState::new(),
+/// ));
+/// assert_eq!(build.hash_one(1u8), build.hash_one(1u8));
+/// ```
+pub struct VerifyOnceBuildHasher<B: BuildHasher, const PF: ProtocolFlags> {
+    build: B,
+    verified: AtomicBool,
+}
+
+impl<B: BuildHasher, const PF: ProtocolFlags> VerifyOnceBuildHasher<B, PF> {
+    pub const fn new(build: B) -> Self {
+        Self {
+            build,
+            verified: AtomicBool::new(false),
+        }
+    }
+    pub fn into_inner(self) -> B {
+        self.build
+    }
+    #[track_caller]
+    fn verify(&self) {
+        for hash in [0, 0x0123_4567_89AB_CDEF, u64::MAX] {
+            let mut hasher = self.build.build_hasher();
+            // Not crate::inject: With `chk-hash`, that would assert the result itself (rather than
+            // describing the protocol).
+            crate::signal::inject_without_chk_hash::<_, PF>(&mut hasher, hash);
+            let result = hasher.finish();
+            if result != hash {
+                panic!(
+                    "Protocol {:?} (signal via {:?}, hash via {:?}): injected {hash:#x}, but the \
+                     Hasher returned {result:#x}.",
+                    PF,
+                    crate::try_signal_via(PF),
+                    crate::try_hash_via(PF)
+                );
+            }
+        }
+    }
+}
+
+impl<B: BuildHasher + Clone, const PF: ProtocolFlags> Clone for VerifyOnceBuildHasher<B, PF> {
+    fn clone(&self) -> Self {
+        Self {
+            build: self.build.clone(),
+            verified: AtomicBool::new(self.verified.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl<B: BuildHasher, const PF: ProtocolFlags> BuildHasher for VerifyOnceBuildHasher<B, PF> {
+    type Hasher = B::Hasher;
+
+    #[track_caller]
+    fn build_hasher(&self) -> Self::Hasher {
+        // If more threads verify at the same time, they all do the same (harmless) work.
+        if !self.verified.load(Ordering::Relaxed) {
+            self.verify();
+            self.verified.store(true, Ordering::Relaxed);
+        }
+        self.build.build_hasher()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SignalledInjectionBuildHasher;
+    use core::cell::Cell;
+    use std::hash::RandomState;
+
+    const PF: ProtocolFlags = crate::new::u8_stream::signal_first::u64();
+
+    /// Counts [BuildHasher::build_hasher] calls.
+    struct Counting<B> {
+        build: B,
+        built: Cell<usize>,
+    }
+    impl<B: BuildHasher> BuildHasher for Counting<B> {
+        type Hasher = B::Hasher;
+        fn build_hasher(&self) -> B::Hasher {
+            self.built.set(self.built.get() + 1);
+            self.build.build_hasher()
+        }
+    }
+
+    #[test]
+    fn verifies_once() {
+        let build = VerifyOnceBuildHasher::<_, PF>::new(Counting {
+            build: SignalledInjectionBuildHasher::<_, _, PF>::new(RandomState::new()),
+            built: Cell::new(0),
+        });
+        let mut hasher = build.build_hasher();
+        crate::inject::<_, PF>(&mut hasher, 42);
+        assert_eq!(hasher.finish(), 42);
+        let _ = build.build_hasher();
+        let _ = build.build_hasher();
+        // 3 for the check, and 3 for the callers.
+        assert_eq!(build.into_inner().built.get(), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "signal via Some(U8Stream), hash via Some(U64)): injected 0x0")]
+    fn not_signalled() {
+        let build = VerifyOnceBuildHasher::<_, PF>::new(RandomState::new());
+        let _ = build.build_hasher();
+    }
+}
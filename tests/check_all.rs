@@ -0,0 +1,59 @@
+//! With all checks enabled, a correct use of the protocols passes every assertion. Run with
+//! `cargo test --features check-all,testing` (and optionally, on nightly, with `mx` and `hpe`, to
+//! include more protocols).
+#![cfg(all(feature = "check-all", feature = "testing"))]
+#![cfg_attr(feature = "flags", feature(adt_const_params))]
+
+use core::hash::{BuildHasherDefault, Hash, Hasher};
+use hash_injector::testing::assert_round_trip;
+use hash_injector::{ProtocolFlags, SignalledInjectionBuildHasher, inject, new};
+use std::collections::HashSet;
+use std::hash::DefaultHasher;
+
+#[derive(PartialEq, Eq)]
+struct Key(u64);
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        inject::<_, KEY_PF>(state, self.0);
+    }
+}
+const KEY_PF: ProtocolFlags = new::u8_stream::signal_first::u64();
+
+fn round_trip<const PF: ProtocolFlags>() {
+    assert_round_trip::<_, PF>(
+        BuildHasherDefault::<DefaultHasher>::default(),
+        &[0, 1, 0x0123_4567_89AB_CDEF, u64::MAX],
+    );
+}
+
+#[test]
+fn all_checks_pass() {
+    assert!(hash_injector::active_features().contains(&"check-all"));
+
+    round_trip::<{ new::u8_stream::signal_first::u64() }>();
+    round_trip::<{ new::u8_stream::submit_first::i128() }>();
+    round_trip::<{ new::usize::signal_first::bytes() }>();
+    round_trip::<{ new::usize::submit_first::u128() }>();
+    round_trip::<{ new::trusted::i64() }>();
+    #[cfg(feature = "hpe")]
+    {
+        round_trip::<{ new::len::signal_first::u64() }>();
+        round_trip::<{ new::len_cookie::submit_first::i64() }>();
+    }
+    #[cfg(any(feature = "mx", feature = "ndd"))]
+    round_trip::<{ new::u8s::submit_first::u64() }>();
+    #[cfg(all(feature = "mx", feature = "hpe"))]
+    round_trip::<{ new::str::signal_first::u128() }>();
+}
+
+#[test]
+fn set_of_injecting_keys() {
+    let build = SignalledInjectionBuildHasher::<_, _, KEY_PF>::new(BuildHasherDefault::<
+        DefaultHasher,
+    >::default());
+    let mut set = HashSet::with_hasher(build);
+    assert!(set.insert(Key(1)));
+    assert!(set.insert(Key(2)));
+    assert!(!set.insert(Key(1)));
+    assert_eq!(set.len(), 2);
+}
@@ -0,0 +1,11 @@
+//! With `chk`, protocols that could only reach an `unreachable!()` branch at runtime fail to
+//! compile. (With `flags` cargo feature, such [hash_injector::ProtocolFlags] can't be written.)
+#![cfg(all(feature = "chk", not(feature = "flags")))]
+
+#[test]
+fn chk_const() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/chk_invalid_*.rs");
+    #[cfg(not(any(feature = "mx", feature = "ndd")))]
+    t.compile_fail("tests/ui/chk_disabled_*.rs");
+}
@@ -0,0 +1,7 @@
+//! Dropping results of `#[must_use]` functions warns (here, with `deny`, it fails to compile).
+
+#[test]
+fn must_use() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/must_use_*.rs");
+}
@@ -0,0 +1,14 @@
+use core::hash::BuildHasherDefault;
+use hash_injector::SignalledInjectionBuildHasher;
+use std::hash::DefaultHasher;
+
+// A `u8s` protocol, but neither `mx` nor `ndd` cargo feature is enabled.
+const BUILD: SignalledInjectionBuildHasher<
+    DefaultHasher,
+    BuildHasherDefault<DefaultHasher>,
+    0b000_0000,
+> = SignalledInjectionBuildHasher::new(BuildHasherDefault::new());
+
+fn main() {
+    let _ = BUILD;
+}
@@ -0,0 +1,14 @@
+use core::hash::BuildHasherDefault;
+use hash_injector::SignalledInjectionBuildHasher;
+use std::hash::DefaultHasher;
+
+// Trusted protocols don't signal first.
+const BUILD: SignalledInjectionBuildHasher<
+    DefaultHasher,
+    BuildHasherDefault<DefaultHasher>,
+    0b000_1011,
+> = SignalledInjectionBuildHasher::new(BuildHasherDefault::new());
+
+fn main() {
+    let _ = BUILD;
+}
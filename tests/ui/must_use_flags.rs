@@ -0,0 +1,10 @@
+#![deny(unused_must_use)]
+
+use hash_injector::{flags_from_bits, flags_to_bits, hash_via_bytes, is_valid, new};
+
+fn main() {
+    is_valid(new::trusted::u64());
+    flags_from_bits(0);
+    flags_to_bits(new::trusted::u64());
+    hash_via_bytes(new::trusted::u64());
+}
@@ -0,0 +1,8 @@
+#![deny(unused_must_use)]
+
+use hash_injector::{Fold128, fold128, tag_hash};
+
+fn main() {
+    fold128(1, Fold128::XorHalves);
+    tag_hash(1, 2);
+}